@@ -1,11 +1,27 @@
-use super::Message;
+use super::{Editor, Hit, HistorySearch, InputHistory, Message, MessageStatus, SearchOverlay};
+use crate::config::{Config, Profile};
+use crate::document;
+use crate::history::{export, EntryStatus, ExportFormat, HistoryEntry, HistoryStore};
+use crate::providers::{self, registry, LanguagePair, Notifier, Responder, TranslationProvider};
+use crate::utils::clipboard;
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::mpsc;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DisplayMode {
+    #[serde(alias = "translation")]
     TranslationOnly,  // 仅翻译
+    #[serde(alias = "both")]
     Bilingual,        // 双语对照
+    #[serde(alias = "original")]
     OriginalOnly,     // 仅原文
 }
 
@@ -19,7 +35,16 @@ impl DisplayMode {
         }
     }
 
-    pub fn to_string(&self) -> &str {
+    /// 配置文件中的名称
+    pub fn config_name(self) -> &'static str {
+        match self {
+            Self::TranslationOnly => "translation_only",
+            Self::Bilingual => "bilingual",
+            Self::OriginalOnly => "original_only",
+        }
+    }
+
+    pub fn to_string(self) -> &'static str {
         match self {
             Self::TranslationOnly => "Trans",
             Self::Bilingual => "Both",
@@ -28,56 +53,311 @@ impl DisplayMode {
     }
 }
 
+impl FromStr for DisplayMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "translation_only" | "translation" | "trans" => Ok(Self::TranslationOnly),
+            "bilingual" | "both" => Ok(Self::Bilingual),
+            "original_only" | "original" | "orig" => Ok(Self::OriginalOnly),
+            _ => Err(anyhow!(
+                "Invalid display mode: {} (expected bilingual, translation_only or original_only)",
+                s
+            )),
+        }
+    }
+}
+
+/// 上次渲染时聊天区域的行布局（由渲染写入），用于按行滚动
+#[derive(Default)]
+pub struct ChatViewport {
+    pub height: usize,      // 可见行数
+    pub total: usize,       // 折行后的总行数
+    pub starts: Vec<usize>, // 各消息的起始行
+    pub heights: HashMap<usize, WrappedHeight>, // 按消息id缓存的折行行数
+}
+
+/// 消息在某一宽度下折行后的行数
+pub struct WrappedHeight {
+    pub width: usize,
+    pub key: u64, // 影响折行的状态摘要
+    pub lines: usize,
+}
+
+impl ChatViewport {
+    pub fn max_scroll(&self) -> usize {
+        self.total.saturating_sub(self.height)
+    }
+}
+
 pub enum AppMessage {
     TranslationDelta(usize, String),    // (msg_id, delta)
     TranslationComplete(usize),         // msg_id
     TranslationError(usize, String),    // (msg_id, error)
+    TranslationProvider(usize, Responder), // (msg_id, 实际应答的Provider)
+    CandidateDelta(usize, usize, String), // (msg_id, 列, delta)
+    CandidateComplete(usize, usize),    // (msg_id, 列)
+    CandidateError(usize, usize, String), // (msg_id, 列, error)
+    Notification(String),               // 后台任务的提示（如重试）
 }
 
 pub struct App {
     pub messages: Vec<Message>,
-    pub input: String,
-    pub scroll: usize,
+    pub input: Editor,
+    pub input_history: InputHistory,
+    pub history_search: Option<HistorySearch>,
+    pub search: Option<SearchOverlay>,  // `/` 搜索浮层
+    pub highlight: Option<String>,      // 跳转后在聊天区高亮的搜索词
+    pub scroll: usize,                  // 聊天区域顶部的行号
+    pub follow: bool,                   // 停在底部时跟随新内容
+    pub viewport: RefCell<ChatViewport>,
     pub should_quit: bool,
     pub display_mode: DisplayMode,
     pub notification: Option<(String, Instant)>,
     pub next_msg_id: usize,
+    pub provider: Arc<dyn TranslationProvider>,
     pub provider_name: String,
+    pub languages: LanguagePair,
+    pub config: Config,
+    pub profile_name: Option<String>,
+    pub profile_popup: Option<usize>,   // 选中的profile索引
+    pub selected: Option<usize>,        // 选中的历史消息索引
+    pub editing: Option<usize>,         // 正在编辑、提交时替换的消息索引
+    pub model_popup: Option<usize>,     // 重新翻译时选择的模型索引
+    pub export_popup: Option<usize>,    // 导出格式索引
+    pub compare_mode: bool,
+    pub markdown: bool,                 // 按Markdown结构翻译
+    pub compare_providers: Vec<Arc<dyn TranslationProvider>>,
+    pub verbose: bool,
+    pub history: Option<HistoryStore>,  // 翻译历史，未启用时为None
+    pub session: String,
     pub tx: mpsc::UnboundedSender<AppMessage>,
     pub rx: mpsc::UnboundedReceiver<AppMessage>,
 }
 
 impl App {
-    pub fn new(provider_name: String) -> Self {
-        let (tx, rx) = mpsc::unbounded_channel();
+    /// 创建App及其Provider链（重试与fallback切换显示为通知）
+    pub fn new(
+        provider_name: &str,
+        profile: Option<&Profile>,
+        languages: LanguagePair,
+        config: Config,
+        profile_name: Option<String>,
+        verbose: bool,
+    ) -> Result<Self> {
+        let channel = mpsc::unbounded_channel();
+        let provider = registry::create_chain(
+            provider_name,
+            &config,
+            profile,
+            verbose,
+            Some(Self::notifier(&channel.0)),
+        )?;
+
+        let input_history = InputHistory::load(
+            Config::data_dir().map(|dir| dir.join("input_history.jsonl")),
+            config.ui.input_history_size,
+        );
+
+        let history = if config.history.enabled {
+            match HistoryStore::open_default() {
+                Ok(store) => Some(store),
+                Err(e) => {
+                    eprintln!("Warning: history disabled: {:#}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(Self {
+            profile_name,
+            history,
+            ..Self::with_provider(provider, languages, config, input_history, verbose, channel)
+        })
+    }
 
+    /// 用现成的Provider创建App（不读写历史记录）
+    fn with_provider(
+        provider: Arc<dyn TranslationProvider>,
+        languages: LanguagePair,
+        config: Config,
+        input_history: InputHistory,
+        verbose: bool,
+        (tx, rx): (mpsc::UnboundedSender<AppMessage>, mpsc::UnboundedReceiver<AppMessage>),
+    ) -> Self {
         Self {
             messages: Vec::new(),
-            input: String::new(),
+            input: Editor::default(),
+            input_history,
+            history_search: None,
+            search: None,
+            highlight: None,
             scroll: 0,
+            follow: true,
+            viewport: RefCell::default(),
             should_quit: false,
-            display_mode: DisplayMode::Bilingual,
+            display_mode: config.display.mode,
             notification: None,
             next_msg_id: 0,
-            provider_name,
+            provider_name: provider.name().to_string(),
+            provider,
+            languages,
+            config,
+            profile_name: None,
+            profile_popup: None,
+            selected: None,
+            editing: None,
+            model_popup: None,
+            export_popup: None,
+            markdown: false,
+            compare_mode: false,
+            compare_providers: Vec::new(),
+            verbose,
+            history: None,
+            session: HistoryEntry::new_session(),
             tx,
             rx,
         }
     }
 
+    /// 载入上次会话的记录，之后的翻译继续记入该会话
+    pub fn resume_last_session(&mut self) -> Result<usize> {
+        let Some(store) = &self.history else {
+            return Err(anyhow!("History is disabled"));
+        };
+        let entries = store.last_session()?;
+        for entry in &entries {
+            let message = Message::from_entry(self.next_msg_id, entry);
+            self.next_msg_id += 1;
+            self.messages.push(message);
+        }
+        if let Some(entry) = entries.last() {
+            self.session = entry.session.clone();
+        }
+        self.scroll_to_bottom();
+        Ok(entries.len())
+    }
+
+    /// 翻译结束后写入历史
+    fn record(&mut self, id: usize) {
+        let Some(entry) = self.messages.iter().find(|m| m.id == id).map(|m| self.history_entry(m)) else {
+            return;
+        };
+        if let Some(store) = &mut self.history {
+            if let Err(e) = store.append(entry) {
+                self.show_notification(format!("Failed to save history: {:#}", e));
+            }
+        }
+    }
+
+    /// 消息对应的历史记录（id由存储分配）
+    fn history_entry(&self, message: &Message) -> HistoryEntry {
+        let (status, error) = match &message.status {
+            MessageStatus::Error(error) => (EntryStatus::Error, Some(error.clone())),
+            MessageStatus::Cancelled => (EntryStatus::Cancelled, None),
+            _ => (EntryStatus::Success, None),
+        };
+        HistoryEntry {
+            id: 0,
+            session: self.session.clone(),
+            timestamp: message.timestamp,
+            finished: chrono::Utc::now(),
+            source: self.languages.source.to_string(),
+            target: self.languages.resolved_target().to_string(),
+            provider: message.provider.clone(),
+            model: message.model.clone(),
+            original: message.text.clone(),
+            translation: message.translation.clone(),
+            status,
+            error,
+        }
+    }
+
+    pub fn open_export_popup(&mut self) {
+        if self.messages.is_empty() {
+            self.show_notification("No messages to export");
+        } else {
+            self.export_popup = Some(0);
+        }
+    }
+
+    pub fn move_export_selection(&mut self, delta: isize) {
+        if let Some(selected) = self.export_popup.as_mut() {
+            let count = ExportFormat::ALL.len() as isize;
+            *selected = (*selected as isize + delta).rem_euclid(count) as usize;
+        }
+    }
+
+    /// 把当前会话的消息导出到当前目录下的文件
+    pub fn export_messages(&mut self, format: ExportFormat) {
+        let entries: Vec<HistoryEntry> = self
+            .messages
+            .iter()
+            .filter(|m| !m.is_streaming())
+            .map(|m| self.history_entry(m))
+            .collect();
+        let path = std::path::PathBuf::from(format!(
+            "ai-tran-cli-{}.{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            format.extension()
+        ));
+
+        match export::write(&path, format, &entries) {
+            Ok(()) => self.show_notification(format!(
+                "Exported {} messages as {} to {}",
+                entries.len(),
+                format.name(),
+                path.display()
+            )),
+            Err(e) => self.show_notification(format!("Export failed: {:#}", e)),
+        }
+    }
+
+    /// 更新消息；由进行中变为结束时写入历史（取消的不记录）
+    fn update_message(&mut self, id: usize, update: impl FnOnce(&mut Message)) {
+        let Some(message) = self.messages.iter_mut().find(|m| m.id == id) else {
+            return;
+        };
+        let was_streaming = message.is_streaming();
+        update(message);
+        if was_streaming && !message.is_streaming() && !message.is_cancelled() {
+            self.record(id);
+        }
+    }
+
+    fn notifier(tx: &mpsc::UnboundedSender<AppMessage>) -> Notifier {
+        let tx = tx.clone();
+        Arc::new(move |message| {
+            let _ = tx.send(AppMessage::Notification(message));
+        })
+    }
+
     pub fn toggle_display_mode(&mut self) {
         self.display_mode = self.display_mode.next();
         self.show_notification(format!("Display mode: {}", self.display_mode.to_string()));
     }
 
+    /// 切换鼠标捕获：开启时滚轮滚动聊天记录，关闭时可直接用鼠标选择文字
+    pub fn toggle_mouse_capture(&mut self) {
+        let enabled = !crate::ui::terminal::mouse_capture();
+        match crate::ui::terminal::set_mouse_capture(enabled) {
+            Ok(()) if enabled => self.show_notification("Mouse: wheel scrolling (Alt+M for text selection)"),
+            Ok(()) => self.show_notification("Mouse: text selection (Alt+M for wheel scrolling)"),
+            Err(e) => self.show_notification(format!("Failed to toggle mouse capture: {}", e)),
+        }
+    }
+
     pub fn show_notification(&mut self, msg: impl Into<String>) {
         self.notification = Some((msg.into(), Instant::now()));
     }
 
     pub fn get_notification(&self) -> Option<&str> {
         if let Some((msg, time)) = &self.notification {
-            // 3秒后自动消失
-            if time.elapsed().as_secs() < 3 {
+            // 超过配置的时长后自动消失
+            if time.elapsed().as_secs() < self.config.ui.notification_duration {
                 return Some(msg);
             }
         }
@@ -95,33 +375,586 @@ impl App {
         self.scroll_to_bottom();
     }
 
+    /// 提交输入框的内容并记入输入历史；编辑中的消息被原位替换
+    pub fn submit_input(&mut self) {
+        let text = self.input.take();
+        if let Err(e) = self.input_history.push(&text) {
+            self.show_notification(format!("Failed to save input history: {}", e));
+        }
+        match self.editing.take().filter(|&index| index < self.messages.len()) {
+            Some(index) => {
+                self.messages[index].text = text;
+                self.rerun(index, None);
+                self.show_notification(format!("Resubmitted #{}", index + 1));
+            }
+            None => self.submit(text),
+        }
+    }
+
+    /// Up：光标在第一行时调出上一条历史输入
+    pub fn input_up(&mut self) {
+        if !self.input.move_up() {
+            if let Some(text) = self.input_history.previous(self.input.text()) {
+                self.input.set_text(text);
+            }
+        }
+    }
+
+    /// Down：光标在最后一行时调出下一条历史输入
+    pub fn input_down(&mut self) {
+        if !self.input.move_down() {
+            if let Some(text) = self.input_history.next() {
+                self.input.set_text(text);
+            }
+        }
+    }
+
+    /// Ctrl+R：开始反向搜索，搜索中再按则找更早的匹配
+    pub fn search_history(&mut self) {
+        match &mut self.history_search {
+            Some(search) => {
+                let before = search.matched.unwrap_or(self.input_history.len());
+                if let Some(index) = self.input_history.search(&search.query, before) {
+                    search.matched = Some(index);
+                }
+            }
+            None => {
+                self.history_search = Some(HistorySearch {
+                    query: String::new(),
+                    matched: None,
+                    saved: self.input.text().to_string(),
+                });
+            }
+        }
+    }
+
+    /// 修改搜索词后从最新的输入重新查找
+    pub fn update_history_search(&mut self, edit: impl FnOnce(&mut String)) {
+        if let Some(search) = &mut self.history_search {
+            edit(&mut search.query);
+            search.matched = self.input_history.search(&search.query, self.input_history.len());
+        }
+    }
+
+    /// 结束搜索；accept为true时把匹配项放入输入框，否则恢复原内容
+    pub fn finish_history_search(&mut self, accept: bool) {
+        let Some(search) = self.history_search.take() else {
+            return;
+        };
+        let matched = search.matched.and_then(|index| self.input_history.get(index));
+        match matched {
+            Some(text) if accept => self.input.set_text(text),
+            _ => self.input.set_text(search.saved),
+        }
+    }
+
+    /// 提交文本并启动流式翻译任务
+    pub fn submit(&mut self, text: String) {
+        if self.compare_mode {
+            self.submit_compare(text);
+            return;
+        }
+
+        let mut message = self.create_message(text);
+        self.spawn_translation(&mut message, Arc::clone(&self.provider));
+        self.add_message(message);
+    }
+
+    /// 启动单个Provider的流式翻译任务，结果写回message
+    fn spawn_translation(&self, message: &mut Message, provider: Arc<dyn TranslationProvider>) {
+        message.start_streaming();
+        message.model = provider.model().to_string();
+        let msg_id = message.id;
+        let text = message.text.clone();
+
+        let tx = self.tx.clone();
+        if self.markdown && !provider.supports_placeholders() {
+            let _ = tx.send(AppMessage::TranslationError(msg_id, document::markdown::UNSUPPORTED.to_string()));
+            return;
+        }
+        let languages = self.languages.clone();
+        // 长文档分块翻译，各块译文按顺序作为增量输出
+        let document = if self.markdown {
+            Some((document::markdown::split(&text, self.config.document.chunk_tokens), self.config.document.clone()))
+        } else {
+            document::needs_chunking(&text, &self.config.document)
+                .then(|| (document::split(&text, &self.config.document), self.config.document.clone()))
+        };
+
+        let task = tokio::spawn(async move {
+            let response = match document {
+                Some((chunks, config)) => Ok(document::translate(Arc::clone(&provider), chunks, &languages, &config)),
+                None => provider
+                    .translate_stream_named(&text, &languages)
+                    .await
+                    .map(|(responder, stream)| providers::named(responder, stream)),
+            };
+            match response {
+                Ok(mut stream) => {
+                    // 分块翻译时各块可能由不同的Provider应答，显示最近一个
+                    let mut current = None;
+                    while let Some(result) = stream.next().await {
+                        match result {
+                            Ok((responder, delta)) => {
+                                if let Some(responder) = responder.filter(|r| current.as_ref() != Some(r)) {
+                                    current = Some(responder.clone());
+                                    let _ = tx.send(AppMessage::TranslationProvider(msg_id, responder));
+                                }
+                                if !delta.is_empty() {
+                                    let _ = tx.send(AppMessage::TranslationDelta(msg_id, delta));
+                                }
+                            }
+                            Err(e) => {
+                                let _ = tx.send(AppMessage::TranslationError(msg_id, e.to_string()));
+                                return;
+                            }
+                        }
+                    }
+                    let _ = tx.send(AppMessage::TranslationComplete(msg_id));
+                }
+                Err(e) => {
+                    let _ = tx.send(AppMessage::TranslationError(msg_id, e.to_string()));
+                }
+            }
+        });
+        message.tasks.push(task.abort_handle());
+    }
+
+    /// 对比模式：每个Provider一个并发的流式任务
+    fn submit_compare(&mut self, text: String) {
+        let id = self.next_msg_id;
+        self.next_msg_id += 1;
+        let mut message = Message::compare(id, text, self.compare_labels());
+        self.spawn_compare(&mut message);
+        self.add_message(message);
+    }
+
+    fn compare_labels(&self) -> Vec<String> {
+        self.compare_providers
+            .iter()
+            .map(|provider| format!("{} {}", provider.name(), provider.model()))
+            .collect()
+    }
+
+    fn spawn_compare(&self, message: &mut Message) {
+        let id = message.id;
+        for (column, provider) in self.compare_providers.iter().enumerate() {
+            let tx = self.tx.clone();
+            let provider = Arc::clone(provider);
+            let languages = self.languages.clone();
+            let text = message.text.clone();
+
+            let task = tokio::spawn(async move {
+                let mut stream = match provider.translate_stream(&text, &languages).await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        let _ = tx.send(AppMessage::CandidateError(id, column, e.to_string()));
+                        return;
+                    }
+                };
+                while let Some(result) = stream.next().await {
+                    match result {
+                        Ok(delta) => {
+                            if !delta.is_empty() {
+                                let _ = tx.send(AppMessage::CandidateDelta(id, column, delta));
+                            }
+                        }
+                        Err(e) => {
+                            let _ = tx.send(AppMessage::CandidateError(id, column, e.to_string()));
+                            return;
+                        }
+                    }
+                }
+                let _ = tx.send(AppMessage::CandidateComplete(id, column));
+            });
+            message.tasks.push(task.abort_handle());
+        }
+    }
+
+    /// 选中上/下一条历史消息（delta<0向上）
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.messages.is_empty() {
+            return;
+        }
+        let last = self.messages.len() - 1;
+        let index = match self.selected {
+            Some(index) => (index as isize + delta).clamp(0, last as isize) as usize,
+            None => last,
+        };
+        self.selected = Some(index);
+        self.follow = false;
+        if let Some(&start) = self.viewport.borrow().starts.get(index) {
+            self.scroll = start;
+        }
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected = None;
+        self.highlight = None;
+        self.scroll_to_bottom();
+    }
+
+    /// 打开 `/` 搜索：当前消息与其他会话的历史记录
+    pub fn open_search(&mut self) {
+        let entries = match &self.history {
+            Some(store) => match store.load() {
+                Ok(mut entries) => {
+                    entries.retain(|entry| entry.session != self.session);
+                    entries
+                }
+                Err(e) => {
+                    self.show_notification(format!("Failed to load history: {:#}", e));
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+        self.search = Some(SearchOverlay::new(entries));
+    }
+
+    /// 修改搜索词并重新匹配
+    pub fn update_search(&mut self, edit: impl FnOnce(&mut String)) {
+        if let Some(search) = &mut self.search {
+            edit(&mut search.query);
+            search.update(&self.messages);
+        }
+    }
+
+    /// 跳转到选中的结果；其他会话的记录先加入当前列表
+    pub fn jump_to_search_hit(&mut self) {
+        let Some(search) = self.search.take() else {
+            return;
+        };
+        let index = match search.selected_hit() {
+            Some(Hit::Message(index)) => index,
+            Some(Hit::Entry(index)) => {
+                let message = Message::from_entry(self.next_msg_id, &search.entries[index]);
+                self.next_msg_id += 1;
+                self.messages.push(message);
+                self.messages.len() - 1
+            }
+            None => return,
+        };
+
+        self.selected = Some(index);
+        self.follow = false;
+        if let Some(&start) = self.viewport.borrow().starts.get(index) {
+            self.scroll = start;
+        }
+        // 新加入的消息在下次渲染后才有行号，先滚到底部
+        if index >= self.viewport.borrow().starts.len() {
+            self.follow = true;
+        }
+        self.highlight = Some(search.query);
+    }
+
+    /// 复制选中结果的译文
+    pub fn copy_search_hit(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+        let translation = match search.selected_hit() {
+            Some(Hit::Message(index)) => self.messages[index].translation.clone(),
+            Some(Hit::Entry(index)) => search.entries[index].translation.clone(),
+            None => return,
+        };
+        if clipboard::copy_text(&translation) {
+            self.search = None;
+            self.show_notification("Copied translation to clipboard");
+        }
+    }
+
+    /// 操作对象：选中的消息，未选中时为最新一条
+    fn target_index(&self) -> Option<usize> {
+        self.selected
+            .filter(|&index| index < self.messages.len())
+            .or_else(|| self.messages.len().checked_sub(1))
+    }
+
+    /// 重新翻译消息；provider为空时使用当前Provider（对比消息用对比Provider）
+    pub fn regenerate(&mut self, provider: Option<Arc<dyn TranslationProvider>>) {
+        let Some(index) = self.target_index() else {
+            self.show_notification("No message to regenerate");
+            return;
+        };
+
+        self.rerun(index, provider);
+        self.show_notification(format!("Regenerating #{}", index + 1));
+    }
+
+    /// 中止并重新翻译指定消息
+    fn rerun(&mut self, index: usize, provider: Option<Arc<dyn TranslationProvider>>) {
+        let mut message = self.messages.remove(index);
+        let compare = provider.is_none() && message.is_compare() && !self.compare_providers.is_empty();
+        message.reset();
+        // 换用新id，被中止的任务已在队列中的事件不会再作用于本次结果
+        message.id = self.next_msg_id;
+        self.next_msg_id += 1;
+        if compare {
+            message.start_compare(self.compare_labels());
+            self.spawn_compare(&mut message);
+        } else {
+            let provider = provider.unwrap_or_else(|| Arc::clone(&self.provider));
+            message.provider = provider.name().to_string();
+            self.spawn_translation(&mut message, provider);
+        }
+        self.messages.insert(index, message);
+    }
+
+    /// 把消息原文放回输入框，编辑后重新提交
+    pub fn edit_message(&mut self) {
+        let Some(index) = self.target_index() else {
+            self.show_notification("No message to edit");
+            return;
+        };
+        self.input.set_text(self.messages[index].text.clone());
+        self.editing = Some(index);
+        self.clear_selection();
+        self.show_notification(format!("Editing #{} (Enter to resubmit)", index + 1));
+    }
+
+    /// 放弃编辑，清空输入框
+    pub fn cancel_edit(&mut self) {
+        if let Some(index) = self.editing.take() {
+            self.input.take();
+            self.show_notification(format!("Stopped editing #{}", index + 1));
+        }
+    }
+
+    /// 可用于重新翻译的模型：profile与 [compare] providers 中的条目
+    pub fn model_choices(&self) -> Vec<String> {
+        let mut choices = self.profile_names();
+        for entry in &self.config.compare.providers {
+            if !choices.contains(entry) {
+                choices.push(entry.clone());
+            }
+        }
+        choices
+    }
+
+    pub fn open_model_popup(&mut self) {
+        if self.target_index().is_none() {
+            self.show_notification("No message to regenerate");
+        } else if self.model_choices().is_empty() {
+            self.show_notification("No other models configured (add profiles or [compare] providers)");
+        } else {
+            self.model_popup = Some(0);
+        }
+    }
+
+    pub fn move_model_selection(&mut self, delta: isize) {
+        let count = self.model_choices().len();
+        if let Some(selected) = self.model_popup.as_mut() {
+            if count > 0 {
+                *selected = (*selected as isize + delta).rem_euclid(count as isize) as usize;
+            }
+        }
+    }
+
+    /// 用指定的profile或provider[:model]重新翻译
+    pub fn regenerate_with(&mut self, entry: &str) -> Result<()> {
+        let provider = registry::create_compare(
+            &[entry.to_string()],
+            &self.config,
+            self.verbose,
+            Some(Self::notifier(&self.tx)),
+        )?
+        .remove(0);
+        self.regenerate(Some(provider));
+        Ok(())
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.messages.iter().any(Message::is_streaming)
+    }
+
+    /// 取消最近一条进行中的翻译
+    pub fn cancel_latest(&mut self) {
+        match self.messages.iter().rev().find(|m| m.is_streaming()).map(|m| m.id) {
+            Some(id) => {
+                self.update_message(id, Message::cancel);
+                self.show_notification("Translation cancelled");
+            }
+            None => self.show_notification("No translation in progress"),
+        }
+    }
+
+    /// 切换对比模式（首次开启时按 [compare] providers 创建Provider）
+    pub fn toggle_compare_mode(&mut self) {
+        if self.compare_mode {
+            self.compare_mode = false;
+            self.show_notification("Compare mode off");
+            return;
+        }
+
+        if self.compare_providers.is_empty() {
+            let entries = &self.config.compare.providers;
+            if entries.len() < 2 {
+                self.show_notification("Compare mode needs at least 2 entries in [compare] providers");
+                return;
+            }
+            match registry::create_compare(entries, &self.config, self.verbose, Some(Self::notifier(&self.tx))) {
+                Ok(providers) => self.compare_providers = providers,
+                Err(e) => {
+                    self.show_notification(format!("Compare mode unavailable: {}", e));
+                    return;
+                }
+            }
+        }
+
+        self.compare_mode = true;
+        self.show_notification(format!(
+            "Compare mode on ({} providers, Alt+1-9 picks the result to copy)",
+            self.compare_providers.len()
+        ));
+    }
+
+    /// 为最新的对比消息选择用于复制的结果
+    pub fn select_candidate(&mut self, index: usize) {
+        let Some(message) = self.messages.iter_mut().rev().find(|m| m.is_compare()) else {
+            self.show_notification("No compare result to pick from");
+            return;
+        };
+        if message.select(index) {
+            let label = message.candidates[index].label.clone();
+            self.show_notification(format!("Picked {} (column {})", label, index + 1));
+        } else {
+            self.show_notification(format!("Column {} not found", index + 1));
+        }
+    }
+
+    pub fn profile_names(&self) -> Vec<String> {
+        self.config.profiles.keys().cloned().collect()
+    }
+
+    pub fn open_profile_popup(&mut self) {
+        let names = self.profile_names();
+        if names.is_empty() {
+            self.show_notification("No profiles defined in config file");
+            return;
+        }
+        let current = self
+            .profile_name
+            .as_ref()
+            .and_then(|name| names.iter().position(|n| n == name))
+            .unwrap_or(0);
+        self.profile_popup = Some(current);
+    }
+
+    pub fn move_profile_selection(&mut self, delta: isize) {
+        let count = self.config.profiles.len();
+        if let Some(selected) = self.profile_popup.as_mut() {
+            if count > 0 {
+                *selected = (*selected as isize + delta).rem_euclid(count as isize) as usize;
+            }
+        }
+    }
+
+    /// 切换到指定profile，重新创建Provider（进行中的翻译不受影响）
+    pub fn switch_profile(&mut self, name: &str) -> Result<()> {
+        let (_, profile) = self.config.profile(name)?;
+        let provider_name = self.config.provider_name(None, Some(profile));
+        let languages = self.config.languages(None, None, Some(profile))?;
+        let provider = registry::create_chain(
+            &provider_name,
+            &self.config,
+            Some(profile),
+            self.verbose,
+            Some(Self::notifier(&self.tx)),
+        )?;
+
+        self.provider_name = provider.name().to_string();
+        self.provider = provider;
+        self.languages = languages;
+        self.profile_name = Some(name.to_string());
+        self.show_notification(format!(
+            "Switched to profile {} ({}, {})",
+            name,
+            self.provider_name,
+            self.provider.model()
+        ));
+        Ok(())
+    }
+
+    /// 当前顶部行号（跟随时为底部）
+    pub fn scroll_offset(&self) -> usize {
+        let max = self.viewport.borrow().max_scroll();
+        if self.follow {
+            max
+        } else {
+            self.scroll.min(max)
+        }
+    }
+
+    /// 按行滚动（负数向上）；滚到底部后恢复跟随
+    pub fn scroll_by(&mut self, delta: isize) {
+        let max = self.viewport.borrow().max_scroll();
+        let offset = (self.scroll_offset() as isize + delta).clamp(0, max as isize) as usize;
+        self.scroll = offset;
+        self.follow = offset >= max;
+    }
+
+    /// 按页滚动，保留一行上下文
+    pub fn scroll_page(&mut self, pages: isize) {
+        let page = self.viewport.borrow().height.saturating_sub(1).max(1) as isize;
+        self.scroll_by(pages * page);
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+        self.follow = false;
+    }
+
     pub fn scroll_to_bottom(&mut self) {
-        self.scroll = self.messages.len().saturating_sub(1);
+        self.follow = true;
     }
 
     pub fn clear_history(&mut self) {
         self.messages.clear();
         self.scroll = 0;
+        self.follow = true;
+        self.selected = None;
+        self.editing = None;
         self.show_notification("History cleared");
     }
 
     pub fn handle_translation_update(&mut self, msg: AppMessage) {
         match msg {
             AppMessage::TranslationDelta(id, delta) => {
-                if let Some(message) = self.messages.iter_mut().find(|m| m.id == id) {
-                    message.append_translation(&delta);
-                }
+                self.update_message(id, |message| message.append_translation(&delta));
             }
             AppMessage::TranslationComplete(id) => {
-                if let Some(message) = self.messages.iter_mut().find(|m| m.id == id) {
-                    message.complete_translation();
+                self.update_message(id, Message::complete_translation);
+                // 自动复制最新翻译
+                if self.config.clipboard.auto_copy_latest
+                    && self.messages.last().is_some_and(|m| m.id == id)
+                {
+                    if let Some(translation) = self.get_latest_translation() {
+                        if clipboard::copy_text(&translation) {
+                            self.show_notification("Copied latest translation to clipboard");
+                        }
+                    }
                 }
             }
             AppMessage::TranslationError(id, error) => {
-                if let Some(message) = self.messages.iter_mut().find(|m| m.id == id) {
-                    message.set_error(error);
-                }
+                self.update_message(id, |message| message.set_error(error));
+            }
+            AppMessage::TranslationProvider(id, responder) => {
+                self.update_message(id, |message| {
+                    message.provider = responder.name;
+                    message.model = responder.model;
+                });
             }
+            AppMessage::CandidateDelta(id, column, delta) => {
+                self.update_message(id, |message| message.append_candidate(column, &delta));
+            }
+            AppMessage::CandidateComplete(id, column) => {
+                self.update_message(id, |message| message.finish_candidate(column, None));
+            }
+            AppMessage::CandidateError(id, column, error) => {
+                self.update_message(id, |message| message.finish_candidate(column, Some(error)));
+            }
+            AppMessage::Notification(message) => self.show_notification(message),
         }
     }
 
@@ -147,3 +980,192 @@ impl App {
             .collect()
     }
 }
+
+#[cfg(test)]
+impl App {
+    /// 测试用App：默认配置，不读写输入历史与翻译历史
+    pub fn for_tests(provider: Arc<dyn TranslationProvider>) -> Self {
+        Self::with_provider(
+            provider,
+            LanguagePair::default(),
+            Config::default(),
+            InputHistory::load(None, 100),
+            false,
+            mpsc::unbounded_channel(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::fake::FakeProvider;
+    use std::time::Duration;
+
+    fn app(provider: &Arc<FakeProvider>) -> App {
+        App::for_tests(provider.clone())
+    }
+
+    /// 处理事件直到没有消息在翻译
+    async fn settle(app: &mut App) {
+        while app.is_streaming() {
+            let msg = tokio::time::timeout(Duration::from_secs(5), app.rx.recv())
+                .await
+                .expect("translation did not finish")
+                .unwrap();
+            app.handle_translation_update(msg);
+        }
+    }
+
+    async fn submit(app: &mut App, text: &str) {
+        app.input.set_text(text.to_string());
+        app.submit_input();
+        settle(app).await;
+    }
+
+    #[tokio::test]
+    async fn regenerate_ignores_events_from_the_aborted_run() {
+        let mut app = app(&Arc::new(FakeProvider::new("fake", vec![])));
+        let mut message = app.create_message("hello".to_string());
+        message.start_streaming();
+        let old_id = message.id;
+        app.add_message(message);
+        app.handle_translation_update(AppMessage::TranslationDelta(old_id, "stale".to_string()));
+
+        app.regenerate(None);
+        let new_id = app.messages[0].id;
+        assert_ne!(new_id, old_id);
+
+        // 旧任务中止前已发出的事件
+        app.handle_translation_update(AppMessage::TranslationDelta(old_id, " more".to_string()));
+        app.handle_translation_update(AppMessage::TranslationComplete(old_id));
+        assert!(app.messages[0].is_streaming());
+        assert_eq!(app.messages[0].translation, "");
+
+        app.handle_translation_update(AppMessage::TranslationDelta(new_id, "fresh".to_string()));
+        app.handle_translation_update(AppMessage::TranslationComplete(new_id));
+        assert_eq!(app.messages[0].translation, "fresh");
+        assert!(app.messages[0].translation_complete);
+    }
+
+    #[tokio::test]
+    async fn regenerated_message_is_not_overwritten_by_the_stale_stream() {
+        let provider = Arc::new(FakeProvider::new("fake", vec![Err(502), Ok("second")]));
+        let mut app = app(&provider);
+        app.input.set_text("hello".to_string());
+        app.submit_input();
+        // 第一次翻译的事件已在队列中，尚未处理
+        let stale = app.rx.recv().await.unwrap();
+
+        app.regenerate(None);
+        app.handle_translation_update(stale);
+        settle(&mut app).await;
+
+        assert_eq!(provider.calls(), 2);
+        assert_eq!(app.messages.len(), 1);
+        assert_eq!(app.messages[0].translation, "second");
+        assert_eq!(app.messages[0].status, MessageStatus::Success);
+    }
+
+    #[tokio::test]
+    async fn resubmitting_an_edit_replaces_the_edited_message() {
+        let provider = Arc::new(FakeProvider::new("fake", vec![]));
+        let mut app = app(&provider);
+        for text in ["one", "two", "three"] {
+            submit(&mut app, text).await;
+        }
+
+        app.selected = Some(1);
+        app.edit_message();
+        assert_eq!(app.input.text(), "two");
+        assert_eq!(app.selected, None);
+        submit(&mut app, "two, edited").await;
+
+        let texts: Vec<_> = app.messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, ["one", "two, edited", "three"]);
+        let translations: Vec<_> = app.messages.iter().map(|m| m.translation.as_str()).collect();
+        assert_eq!(translations, ["one", "two, edited", "three"]);
+        assert_eq!(provider.calls(), 4);
+        assert_eq!(app.editing, None);
+
+        // 编辑完成后的提交追加新消息
+        submit(&mut app, "four").await;
+        assert_eq!(app.messages.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn cancelled_edit_submits_a_new_message() {
+        let mut app = app(&Arc::new(FakeProvider::new("fake", vec![])));
+        submit(&mut app, "one").await;
+
+        app.edit_message();
+        app.cancel_edit();
+        assert!(app.input.is_empty());
+        submit(&mut app, "two").await;
+
+        let texts: Vec<_> = app.messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, ["one", "two"]);
+    }
+
+    #[tokio::test]
+    async fn reverse_search_steps_to_older_matches() {
+        let mut app = app(&Arc::new(FakeProvider::new("fake", vec![])));
+        for text in ["hello world", "goodbye", "hello there"] {
+            submit(&mut app, text).await;
+        }
+        app.input.set_text("draft".to_string());
+
+        app.search_history();
+        app.update_history_search(|query| query.push_str("hel"));
+        assert_eq!(app.history_search.as_ref().unwrap().matched, Some(2));
+        app.search_history();
+        assert_eq!(app.history_search.as_ref().unwrap().matched, Some(0));
+        // 没有更早的匹配时停在原处
+        app.search_history();
+        assert_eq!(app.history_search.as_ref().unwrap().matched, Some(0));
+        app.finish_history_search(true);
+        assert_eq!(app.input.text(), "hello world");
+
+        app.search_history();
+        app.update_history_search(|query| query.push_str("bye"));
+        app.finish_history_search(false);
+        assert_eq!(app.input.text(), "hello world");
+    }
+
+    #[tokio::test]
+    async fn enter_jumps_to_the_selected_search_hit() {
+        let mut app = app(&Arc::new(FakeProvider::new("fake", vec![])));
+        for text in ["apple", "banana", "apricot"] {
+            submit(&mut app, text).await;
+        }
+
+        app.open_search();
+        app.update_search(|query| query.push_str("ap"));
+        app.search.as_mut().unwrap().move_selection(1);
+        assert_eq!(app.search.as_ref().unwrap().selected_hit(), Some(Hit::Message(0)));
+        app.jump_to_search_hit();
+
+        assert!(app.search.is_none());
+        assert_eq!(app.selected, Some(0));
+        assert_eq!(app.highlight.as_deref(), Some("ap"));
+        assert_eq!(app.messages.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn jumping_to_a_stored_entry_appends_it() {
+        let mut app = app(&Arc::new(FakeProvider::new("fake", vec![])));
+        submit(&mut app, "apple").await;
+
+        let mut earlier = app.history_entry(&app.messages[0]);
+        earlier.original = "grape".to_string();
+        earlier.translation = "葡萄".to_string();
+        app.search = Some(SearchOverlay::new(vec![earlier]));
+        app.update_search(|query| query.push_str("grape"));
+        app.jump_to_search_hit();
+
+        assert_eq!(app.messages.len(), 2);
+        assert_eq!(app.selected, Some(1));
+        assert_eq!(app.messages[1].text, "grape");
+        assert_eq!(app.messages[1].translation, "葡萄");
+    }
+}
@@ -0,0 +1,287 @@
+use unicode_width::UnicodeWidthChar;
+
+/// 多行输入框：文本与光标（字节偏移）
+#[derive(Clone, Debug, Default)]
+pub struct Editor {
+    text: String,
+    cursor: usize,
+}
+
+impl Editor {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// 替换全部内容，光标移到末尾
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+    }
+
+    /// 取出内容并清空
+    pub fn take(&mut self) -> String {
+        self.cursor = 0;
+        std::mem::take(&mut self.text)
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// 插入粘贴的文本（统一换行符）
+    pub fn insert_str(&mut self, s: &str) {
+        let s = s.replace("\r\n", "\n").replace('\r', "\n");
+        self.text.insert_str(self.cursor, &s);
+        self.cursor += s.len();
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+            self.text.remove(self.cursor);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.cursor < self.text.len() {
+            self.text.remove(self.cursor);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if let Some(c) = self.text[self.cursor..].chars().next() {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = self.line_start(self.cursor);
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.line_end(self.cursor);
+    }
+
+    /// 移到上一行的相同列；已在第一行时返回false
+    pub fn move_up(&mut self) -> bool {
+        let start = self.line_start(self.cursor);
+        if start == 0 {
+            return false;
+        }
+        let column = display_width(&self.text[start..self.cursor]);
+        let previous = self.line_start(start - 1);
+        self.cursor = self.column_offset(previous, column);
+        true
+    }
+
+    /// 移到下一行的相同列；已在最后一行时返回false
+    pub fn move_down(&mut self) -> bool {
+        let end = self.line_end(self.cursor);
+        if end == self.text.len() {
+            return false;
+        }
+        let column = display_width(&self.text[self.line_start(self.cursor)..self.cursor]);
+        self.cursor = self.column_offset(end + 1, column);
+        true
+    }
+
+    /// Ctrl+W：删除光标前的一个词（连同其后的空白）
+    pub fn delete_word(&mut self) {
+        let before = &self.text[..self.cursor];
+        let trimmed = before.trim_end_matches(|c: char| c.is_whitespace() && c != '\n');
+        let start = match trimmed.char_indices().next_back() {
+            // 光标紧跟换行时只删除换行
+            Some((_, '\n')) if trimmed.len() == before.len() => trimmed.len() - 1,
+            _ => trimmed
+                .char_indices()
+                .rev()
+                .find(|(_, c)| c.is_whitespace())
+                .map_or(0, |(i, c)| i + c.len_utf8()),
+        };
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    /// Ctrl+U：删除光标前到行首的内容
+    pub fn delete_to_line_start(&mut self) {
+        let start = self.line_start(self.cursor);
+        let start = if start == self.cursor { start.saturating_sub(1) } else { start };
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    /// 按显示宽度折行，返回各行与光标所在的 (行, 列)
+    pub fn wrap(&self, width: usize) -> (Vec<String>, (usize, usize)) {
+        let width = width.max(2);
+        let mut rows = vec![String::new()];
+        let mut column = 0;
+        let mut cursor = (0, 0);
+
+        for (offset, c) in self.text.char_indices() {
+            if offset == self.cursor {
+                cursor = (rows.len() - 1, column);
+            }
+            if c == '\n' {
+                rows.push(String::new());
+                column = 0;
+                continue;
+            }
+            let char_width = c.width().unwrap_or(0);
+            if column + char_width > width {
+                rows.push(String::new());
+                column = 0;
+                if offset == self.cursor {
+                    cursor = (rows.len() - 1, 0);
+                }
+            }
+            rows.last_mut().unwrap().push(c);
+            column += char_width;
+        }
+
+        if self.cursor == self.text.len() {
+            // 光标停在行尾且该行已满时放到下一行开头
+            if column >= width {
+                rows.push(String::new());
+                column = 0;
+            }
+            cursor = (rows.len() - 1, column);
+        }
+        (rows, cursor)
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        self.text[..offset].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn line_end(&self, offset: usize) -> usize {
+        self.text[offset..].find('\n').map_or(self.text.len(), |i| offset + i)
+    }
+
+    /// 从行首start起，显示宽度不超过column的位置
+    fn column_offset(&self, start: usize, column: usize) -> usize {
+        let mut used = 0;
+        for (i, c) in self.text[start..].char_indices() {
+            let char_width = c.width().unwrap_or(0);
+            if c == '\n' || used + char_width > column {
+                return start + i;
+            }
+            used += char_width;
+        }
+        self.text.len()
+    }
+}
+
+fn display_width(s: &str) -> usize {
+    s.chars().map(|c| c.width().unwrap_or(0)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_text(text: &str) -> Editor {
+        let mut editor = Editor::default();
+        editor.set_text(text);
+        editor
+    }
+
+    #[test]
+    fn edits_multibyte_text_at_the_cursor() {
+        let mut editor = with_text("你好");
+        editor.move_left();
+        editor.insert_char('a');
+        assert_eq!(editor.text(), "你a好");
+        editor.backspace();
+        editor.backspace();
+        assert_eq!(editor.text(), "好");
+        editor.delete();
+        assert_eq!(editor.text(), "");
+        editor.backspace();
+        assert!(editor.is_empty());
+    }
+
+    #[test]
+    fn pasted_line_endings_are_normalized() {
+        let mut editor = Editor::default();
+        editor.insert_str("a\r\nb\rc");
+        assert_eq!(editor.text(), "a\nb\nc");
+        assert_eq!(editor.cursor, editor.text().len());
+    }
+
+    #[test]
+    fn moves_between_lines_by_display_column() {
+        let mut editor = with_text("你好世界\nabcdef\nxy");
+        assert!(editor.move_up());
+        // 第3行第2列 → 第2行第2列
+        assert_eq!(editor.cursor, "你好世界\nab".len());
+        assert!(editor.move_up());
+        // 第2列落在第一个全角字符之后
+        assert_eq!(editor.cursor, "你".len());
+        assert!(!editor.move_up());
+
+        editor.move_end();
+        assert!(editor.move_down());
+        assert_eq!(editor.cursor, "你好世界\nabcdef".len());
+        assert!(editor.move_down());
+        assert_eq!(editor.cursor, editor.text().len());
+        assert!(!editor.move_down());
+
+        editor.move_home();
+        assert_eq!(editor.cursor, "你好世界\nabcdef\n".len());
+    }
+
+    #[test]
+    fn deletes_words_and_lines() {
+        let mut editor = with_text("hello big  world");
+        editor.delete_word();
+        assert_eq!(editor.text(), "hello big  ");
+        editor.delete_word();
+        assert_eq!(editor.text(), "hello ");
+
+        let mut editor = with_text("first\n");
+        editor.delete_word();
+        assert_eq!(editor.text(), "first");
+
+        let mut editor = with_text("one\ntwo three");
+        editor.delete_to_line_start();
+        assert_eq!(editor.text(), "one\n");
+        editor.delete_to_line_start();
+        assert_eq!(editor.text(), "one");
+    }
+
+    #[test]
+    fn take_clears_the_editor() {
+        let mut editor = with_text("text");
+        assert_eq!(editor.take(), "text");
+        assert!(editor.is_empty());
+        editor.insert_char('x');
+        assert_eq!(editor.text(), "x");
+    }
+
+    #[test]
+    fn wraps_by_display_width_and_tracks_the_cursor() {
+        let (rows, cursor) = with_text("abcdef\n你好世界").wrap(4);
+        assert_eq!(rows, vec!["abcd", "ef", "你好", "世界", ""]);
+        assert_eq!(cursor, (4, 0));
+
+        let mut editor = with_text("abcdef");
+        editor.move_home();
+        editor.move_right();
+        editor.move_right();
+        editor.move_right();
+        editor.move_right();
+        assert_eq!(editor.wrap(4).1, (1, 0));
+        assert_eq!(editor.wrap(10), (vec!["abcdef".to_string()], (0, 4)));
+    }
+}
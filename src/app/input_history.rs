@@ -0,0 +1,208 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+/// 已提交输入的历史（按时间从旧到新），保存为每行一个JSON字符串
+pub struct InputHistory {
+    entries: Vec<String>,
+    limit: usize,
+    path: Option<PathBuf>,
+    position: Option<usize>, // Up/Down浏览到的条目
+    draft: String,           // 开始浏览前输入框的内容
+}
+
+/// Ctrl+R 反向搜索状态
+pub struct HistorySearch {
+    pub query: String,
+    pub matched: Option<usize>,
+    pub saved: String, // 搜索前输入框的内容，取消时恢复
+}
+
+impl InputHistory {
+    /// 读取历史文件；文件不存在或损坏的行直接忽略
+    pub fn load(path: Option<PathBuf>, limit: usize) -> Self {
+        let mut entries: Vec<String> = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let excess = entries.len().saturating_sub(limit);
+        entries.drain(..excess);
+
+        Self {
+            entries,
+            limit,
+            path,
+            position: None,
+            draft: String::new(),
+        }
+    }
+
+    /// 记录一次提交；相同内容只保留最新一条
+    pub fn push(&mut self, text: &str) -> Result<()> {
+        self.position = None;
+        if self.limit == 0 || text.trim().is_empty() {
+            return Ok(());
+        }
+
+        self.entries.retain(|entry| entry != text);
+        self.entries.push(text.to_string());
+        let excess = self.entries.len().saturating_sub(self.limit);
+        self.entries.drain(..excess);
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        let mut content = String::new();
+        for entry in &self.entries {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+        std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// 上一条（更早的）输入；current为开始浏览时输入框的内容
+    pub fn previous(&mut self, current: &str) -> Option<&str> {
+        let index = match self.position {
+            Some(0) => return None,
+            Some(index) => index - 1,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len().checked_sub(1)?
+            }
+        };
+        self.position = Some(index);
+        Some(&self.entries[index])
+    }
+
+    /// 下一条（更新的）输入；越过最新一条时返回浏览前的内容
+    pub fn next(&mut self) -> Option<&str> {
+        let index = self.position? + 1;
+        if index < self.entries.len() {
+            self.position = Some(index);
+            Some(&self.entries[index])
+        } else {
+            self.position = None;
+            Some(&self.draft)
+        }
+    }
+
+    /// 从before往前找包含query的最近一条
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ai-tran-cli-test-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("input_history.jsonl")
+    }
+
+    fn history(entries: &[&str]) -> InputHistory {
+        let mut history = InputHistory::load(None, 100);
+        for entry in entries {
+            history.push(entry).unwrap();
+        }
+        history
+    }
+
+    fn entries(history: &InputHistory) -> Vec<&str> {
+        (0..history.len()).filter_map(|index| history.get(index)).collect()
+    }
+
+    #[test]
+    fn browsing_restores_the_draft() {
+        let mut history = history(&["one", "two"]);
+        assert_eq!(history.previous("draft"), Some("two"));
+        assert_eq!(history.previous("two"), Some("one"));
+        assert_eq!(history.previous("one"), None);
+        assert_eq!(history.next(), Some("two"));
+        assert_eq!(history.next(), Some("draft"));
+        assert_eq!(history.next(), None);
+
+        // 提交后从最新一条重新开始
+        history.push("three").unwrap();
+        assert_eq!(history.previous(""), Some("three"));
+    }
+
+    #[test]
+    fn empty_history_has_nothing_to_recall() {
+        let mut history = history(&[]);
+        assert_eq!(history.previous("draft"), None);
+        assert_eq!(history.next(), None);
+    }
+
+    #[test]
+    fn duplicates_keep_only_the_latest() {
+        let history = history(&["one", "one", "two", "one", "  "]);
+        assert_eq!(entries(&history), ["two", "one"]);
+    }
+
+    #[test]
+    fn trims_to_the_limit() {
+        let path = temp_path("input-limit");
+        let mut history = InputHistory::load(Some(path.clone()), 2);
+        for entry in ["one", "two", "three"] {
+            history.push(entry).unwrap();
+        }
+        assert_eq!(entries(&history), ["two", "three"]);
+
+        // 调小上限后载入时也会截断
+        assert_eq!(entries(&InputHistory::load(Some(path.clone()), 1)), ["three"]);
+
+        let mut disabled = InputHistory::load(Some(path), 0);
+        disabled.push("four").unwrap();
+        assert_eq!(disabled.len(), 0);
+    }
+
+    #[test]
+    fn round_trips_through_jsonl() {
+        let path = temp_path("input-jsonl");
+        let mut history = InputHistory::load(Some(path.clone()), 10);
+        history.push("line one\nline two").unwrap();
+        history.push("say \"hi\"").unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, "\"line one\\nline two\"\n\"say \\\"hi\\\"\"\n");
+
+        std::fs::write(&path, format!("{}not json\n", content)).unwrap();
+        let loaded = InputHistory::load(Some(path), 10);
+        assert_eq!(entries(&loaded), ["line one\nline two", "say \"hi\""]);
+    }
+
+    #[test]
+    fn searches_backwards_from_the_newest() {
+        let history = history(&["hello world", "goodbye", "hello there"]);
+        assert_eq!(history.search("hello", history.len()), Some(2));
+        assert_eq!(history.search("hello", 2), Some(0));
+        assert_eq!(history.search("hello", 0), None);
+        assert_eq!(history.search("missing", history.len()), None);
+        assert_eq!(history.search("", 10), Some(2));
+    }
+}
@@ -1,4 +1,7 @@
+use crate::history::{EntryStatus, HistoryEntry};
 use chrono::{DateTime, Utc};
+use std::time::{Duration, Instant};
+use tokio::task::AbortHandle;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum MessageStatus {
@@ -6,6 +9,35 @@ pub enum MessageStatus {
     Streaming,      // 流式传输中
     Success,        // 翻译成功
     Error(String),  // 翻译失败
+    Cancelled,      // 用户取消（保留已收到的部分）
+}
+
+/// 对比模式下单个Provider的结果
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub label: String,                 // Provider与模型
+    pub translation: String,
+    pub status: MessageStatus,
+    pub started: Instant,
+    pub first_token: Option<Duration>, // 首个token延迟
+    pub elapsed: Option<Duration>,     // 总耗时
+}
+
+impl Candidate {
+    pub fn new(label: String) -> Self {
+        Self {
+            label,
+            translation: String::new(),
+            status: MessageStatus::Streaming,
+            started: Instant::now(),
+            first_token: None,
+            elapsed: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !matches!(self.status, MessageStatus::Pending | MessageStatus::Streaming)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -13,10 +45,14 @@ pub struct Message {
     pub id: usize,
     pub text: String,                  // 用户输入
     pub translation: String,           // 翻译结果（支持增量更新）
-    pub translation_complete: bool,    // 翻译是否完成
+    pub translation_complete: bool,    // 翻译是否完成（取消时为false，部分译文不可复制）
     pub status: MessageStatus,
     pub timestamp: DateTime<Utc>,
-    pub provider: String,
+    pub provider: String,              // 实际应答的Provider
+    pub model: String,
+    pub candidates: Vec<Candidate>,    // 对比模式下各Provider的结果
+    pub selected: usize,               // 选中用于复制的结果
+    pub tasks: Vec<AbortHandle>,       // 进行中的翻译任务，用于取消
 }
 
 impl Message {
@@ -29,6 +65,106 @@ impl Message {
             status: MessageStatus::Pending,
             timestamp: Utc::now(),
             provider,
+            model: String::new(),
+            candidates: Vec::new(),
+            selected: 0,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// 由历史记录恢复的消息
+    pub fn from_entry(id: usize, entry: &HistoryEntry) -> Self {
+        let mut message = Self::new(id, entry.original.clone(), entry.provider.clone());
+        message.translation = entry.translation.clone();
+        message.model = entry.model.clone();
+        message.timestamp = entry.timestamp;
+        message.status = match entry.status {
+            EntryStatus::Success => MessageStatus::Success,
+            EntryStatus::Error => MessageStatus::Error(entry.error.clone().unwrap_or_default()),
+            EntryStatus::Cancelled => MessageStatus::Cancelled,
+        };
+        message.translation_complete = !message.is_cancelled();
+        message
+    }
+
+    /// 对比模式消息：每个Provider一列
+    pub fn compare(id: usize, text: String, labels: Vec<String>) -> Self {
+        let mut message = Self::new(id, text, String::new());
+        message.start_compare(labels);
+        message
+    }
+
+    pub fn start_compare(&mut self, labels: Vec<String>) {
+        self.candidates = labels.into_iter().map(Candidate::new).collect();
+        self.selected = 0;
+        self.status = MessageStatus::Streaming;
+    }
+
+    /// 重新翻译前清空结果（中止仍在进行的请求）
+    pub fn reset(&mut self) {
+        for task in self.tasks.drain(..) {
+            task.abort();
+        }
+        self.translation.clear();
+        self.translation_complete = false;
+        self.candidates.clear();
+        self.selected = 0;
+        self.status = MessageStatus::Pending;
+        self.timestamp = Utc::now();
+    }
+
+    pub fn is_compare(&self) -> bool {
+        !self.candidates.is_empty()
+    }
+
+    pub fn append_candidate(&mut self, index: usize, delta: &str) {
+        if let Some(candidate) = self.candidates.get_mut(index).filter(|c| !c.is_finished()) {
+            if candidate.first_token.is_none() {
+                candidate.first_token = Some(candidate.started.elapsed());
+            }
+            candidate.translation.push_str(delta);
+        }
+        self.sync_candidates();
+    }
+
+    pub fn finish_candidate(&mut self, index: usize, error: Option<String>) {
+        if let Some(candidate) = self.candidates.get_mut(index).filter(|c| !c.is_finished()) {
+            candidate.elapsed = Some(candidate.started.elapsed());
+            candidate.status = match error {
+                Some(error) => MessageStatus::Error(error),
+                None => MessageStatus::Success,
+            };
+        }
+        self.sync_candidates();
+    }
+
+    /// 选择用于复制的结果
+    pub fn select(&mut self, index: usize) -> bool {
+        if index >= self.candidates.len() {
+            return false;
+        }
+        self.selected = index;
+        self.sync_candidates();
+        true
+    }
+
+    /// 以选中的结果作为消息的翻译；全部结束后更新消息状态
+    fn sync_candidates(&mut self) {
+        let Some(selected) = self.candidates.get(self.selected) else {
+            return;
+        };
+        self.translation = selected.translation.clone();
+        self.provider = selected.label.clone();
+        self.translation_complete = selected.status == MessageStatus::Success;
+
+        if self.status == MessageStatus::Streaming && self.candidates.iter().all(Candidate::is_finished) {
+            self.status = if self.candidates.iter().any(|c| c.status == MessageStatus::Success) {
+                MessageStatus::Success
+            } else if self.candidates.iter().any(|c| c.status == MessageStatus::Cancelled) {
+                MessageStatus::Cancelled
+            } else {
+                MessageStatus::Error("All providers failed".to_string())
+            };
         }
     }
 
@@ -37,17 +173,105 @@ impl Message {
         self.translation.clear();
     }
 
+    pub fn is_streaming(&self) -> bool {
+        self.status == MessageStatus::Streaming
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.status == MessageStatus::Cancelled
+    }
+
+    // 取消后仍可能收到已在队列中的更新，忽略即可
     pub fn append_translation(&mut self, delta: &str) {
-        self.translation.push_str(delta);
+        if self.is_streaming() {
+            self.translation.push_str(delta);
+        }
     }
 
     pub fn complete_translation(&mut self) {
-        self.translation_complete = true;
-        self.status = MessageStatus::Success;
+        if self.is_streaming() {
+            self.translation_complete = true;
+            self.status = MessageStatus::Success;
+            self.tasks.clear();
+        }
     }
 
     pub fn set_error(&mut self, error: String) {
-        self.status = MessageStatus::Error(error);
-        self.translation_complete = true;
+        if self.is_streaming() {
+            self.status = MessageStatus::Error(error);
+            self.translation_complete = true;
+            self.tasks.clear();
+        }
+    }
+
+    /// 中止进行中的请求（断开HTTP连接），保留已收到的部分译文，但不视为完成
+    ///
+    /// 对比模式下已有成功的结果时，消息仍按成功处理
+    pub fn cancel(&mut self) {
+        for task in self.tasks.drain(..) {
+            task.abort();
+        }
+        for candidate in &mut self.candidates {
+            if !candidate.is_finished() {
+                candidate.elapsed = Some(candidate.started.elapsed());
+                candidate.status = MessageStatus::Cancelled;
+            }
+        }
+        if self.is_compare() {
+            self.sync_candidates();
+        } else if self.is_streaming() {
+            self.status = MessageStatus::Cancelled;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn streaming(text: &str) -> Message {
+        let mut message = Message::new(1, "source".to_string(), "fake".to_string());
+        message.start_streaming();
+        message.append_translation(text);
+        message
+    }
+
+    #[test]
+    fn cancelled_partial_text_is_not_complete() {
+        let mut message = streaming("partial");
+        message.cancel();
+        assert!(message.is_cancelled());
+        assert!(!message.translation_complete);
+        assert_eq!(message.translation, "partial");
+
+        // 取消后到达的更新被忽略
+        message.append_translation(" more");
+        message.complete_translation();
+        assert!(message.is_cancelled());
+        assert_eq!(message.translation, "partial");
+    }
+
+    #[test]
+    fn completed_translation_is_complete() {
+        let mut message = streaming("done");
+        message.complete_translation();
+        assert_eq!(message.status, MessageStatus::Success);
+        assert!(message.translation_complete);
+    }
+
+    #[test]
+    fn cancelled_compare_keeps_finished_results() {
+        let mut message = Message::compare(1, "source".to_string(), vec!["a".to_string(), "b".to_string()]);
+        message.append_candidate(0, "first");
+        message.finish_candidate(0, None);
+        message.append_candidate(1, "sec");
+        message.cancel();
+
+        assert_eq!(message.status, MessageStatus::Success);
+        assert!(message.translation_complete);
+        assert_eq!(message.candidates[1].status, MessageStatus::Cancelled);
+
+        message.select(1);
+        assert!(!message.translation_complete);
     }
 }
@@ -1,5 +1,11 @@
 pub mod app;
+pub mod editor;
+pub mod input_history;
 pub mod message;
+pub mod search;
 
-pub use app::{App, AppMessage, DisplayMode};
+pub use app::{App, DisplayMode, WrappedHeight};
+pub use editor::Editor;
+pub use input_history::{HistorySearch, InputHistory};
 pub use message::{Message, MessageStatus};
+pub use search::{Hit, SearchOverlay};
@@ -0,0 +1,183 @@
+use super::Message;
+use crate::history::HistoryEntry;
+use crate::utils::fuzzy_match;
+
+/// 最多显示的结果数
+const MAX_RESULTS: usize = 50;
+
+/// 搜索结果来源
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hit {
+    Message(usize), // 当前会话的消息下标
+    Entry(usize),   // 历史记录（其他会话）下标
+}
+
+pub struct SearchResult {
+    pub hit: Hit,
+    pub score: i64,
+    pub original: Vec<usize>,    // 原文中匹配的字符下标
+    pub translation: Vec<usize>, // 译文中匹配的字符下标
+}
+
+/// `/` 搜索浮层：当前消息与历史记录
+pub struct SearchOverlay {
+    pub query: String,
+    pub entries: Vec<HistoryEntry>,
+    pub results: Vec<SearchResult>,
+    pub selected: usize,
+}
+
+impl SearchOverlay {
+    pub fn new(entries: Vec<HistoryEntry>) -> Self {
+        Self {
+            query: String::new(),
+            entries,
+            results: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    /// 按得分排序，同分时较新的在前；选中项仍匹配时保持选中，否则保持位置
+    pub fn update(&mut self, messages: &[Message]) {
+        let previous = self.selected_hit();
+        let current = messages
+            .iter()
+            .enumerate()
+            .map(|(index, m)| (Hit::Message(index), m.text.as_str(), m.translation.as_str()));
+        let stored = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, e)| (Hit::Entry(index), e.original.as_str(), e.translation.as_str()));
+
+        let mut results: Vec<SearchResult> = current
+            .rev()
+            .chain(stored.rev())
+            .filter_map(|(hit, original, translation)| {
+                let original = fuzzy_match(&self.query, original);
+                let translation = fuzzy_match(&self.query, translation);
+                let score = original.iter().chain(&translation).map(|(score, _)| *score).max()?;
+                Some(SearchResult {
+                    hit,
+                    score,
+                    original: original.map(|(_, positions)| positions).unwrap_or_default(),
+                    translation: translation.map(|(_, positions)| positions).unwrap_or_default(),
+                })
+            })
+            .collect();
+        results.sort_by_key(|result| std::cmp::Reverse(result.score));
+        results.truncate(MAX_RESULTS);
+
+        self.results = results;
+        self.selected = previous
+            .and_then(|hit| self.results.iter().position(|result| result.hit == hit))
+            .unwrap_or_else(|| self.selected.min(self.results.len().saturating_sub(1)));
+    }
+
+    pub fn selected_hit(&self) -> Option<Hit> {
+        self.results.get(self.selected).map(|result| result.hit)
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        if !self.results.is_empty() {
+            let last = self.results.len() as isize - 1;
+            self.selected = (self.selected as isize + delta).clamp(0, last) as usize;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::EntryStatus;
+    use chrono::Utc;
+
+    fn message(text: &str, translation: &str) -> Message {
+        let mut message = Message::new(0, text.to_string(), "Fake".to_string());
+        message.translation = translation.to_string();
+        message
+    }
+
+    fn entry(original: &str, translation: &str) -> HistoryEntry {
+        HistoryEntry {
+            id: 1,
+            session: "earlier".to_string(),
+            timestamp: Utc::now(),
+            finished: Utc::now(),
+            source: "auto".to_string(),
+            target: "zh".to_string(),
+            provider: "Fake".to_string(),
+            model: "fake".to_string(),
+            original: original.to_string(),
+            translation: translation.to_string(),
+            status: EntryStatus::Success,
+            error: None,
+        }
+    }
+
+    fn search(query: &str, messages: &[Message], entries: Vec<HistoryEntry>) -> SearchOverlay {
+        let mut search = SearchOverlay::new(entries);
+        search.query = query.to_string();
+        search.update(messages);
+        search
+    }
+
+    fn hits(search: &SearchOverlay) -> Vec<Hit> {
+        search.results.iter().map(|result| result.hit).collect()
+    }
+
+    #[test]
+    fn ranks_by_score_then_recency() {
+        let messages = [message("a cat", "一只猫"), message("c-a-t", ""), message("catalog", "目录"), message("cat", "")];
+        let entries = vec![entry("cat", "猫"), entry("dog", "狗")];
+        let search = search("cat", &messages, entries);
+
+        // 词首的连续匹配得分最高；同分时当前会话与较新的消息在前，不匹配的不出现
+        assert_eq!(
+            hits(&search),
+            [Hit::Message(3), Hit::Message(2), Hit::Entry(0), Hit::Message(0), Hit::Message(1)]
+        );
+        assert_eq!(search.results[3].original, [2, 3, 4]);
+        assert_eq!(search.selected_hit(), Some(Hit::Message(3)));
+    }
+
+    #[test]
+    fn matches_translations() {
+        let messages = [message("hello", "你好"), message("cat", "猫")];
+        let search = search("猫", &messages, Vec::new());
+        assert_eq!(hits(&search), [Hit::Message(1)]);
+        assert_eq!((search.results[0].original.len(), search.results[0].translation.clone()), (0, vec![0]));
+    }
+
+    #[test]
+    fn keeps_the_selected_hit_while_it_still_matches() {
+        let messages = [message("cat food", ""), message("cart", ""), message("cat", "")];
+        let mut search = search("c", &messages, Vec::new());
+        search.move_selection(1);
+        let selected = search.selected_hit();
+
+        search.query.push('a');
+        search.update(&messages);
+        assert_eq!(search.selected_hit(), selected);
+    }
+
+    #[test]
+    fn clamps_the_selection_when_results_shrink() {
+        let messages = [message("cat", ""), message("cart", ""), message("car", "")];
+        let mut search = search("ca", &messages, Vec::new());
+        search.move_selection(2);
+        assert_eq!(search.selected, 2);
+
+        // 选中的结果不再匹配时，选中位置不超出结果数
+        search.query.push_str("rt");
+        search.update(&messages);
+        assert_eq!(search.selected, 0);
+        assert_eq!(search.selected_hit(), Some(Hit::Message(1)));
+
+        search.query.push('x');
+        search.update(&messages);
+        assert_eq!((search.selected, search.selected_hit()), (0, None));
+        search.move_selection(1);
+        assert_eq!(search.selected, 0);
+    }
+}
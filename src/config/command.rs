@@ -0,0 +1,621 @@
+use super::{keys, Config, Profile};
+use crate::providers::{registry, Language, LanguagePair, ProviderError};
+use crate::utils::mask_sensitive;
+use anyhow::{anyhow, Context, Result};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use toml_edit::{value, DocumentMut, Item, Table};
+
+/// `config` 子命令参数
+#[derive(clap::Args, Debug, Default)]
+pub struct ConfigArgs {
+    /// Print the effective configuration (keys masked)
+    #[arg(long, conflicts_with = "check")]
+    pub show: bool,
+
+    /// Send a minimal test request to the configured endpoint
+    #[arg(long)]
+    pub check: bool,
+
+    /// API key to store in the config file
+    #[arg(long = "api-key")]
+    pub api_key: Option<String>,
+
+    /// API base URL to store in the config file
+    #[arg(long = "api-base")]
+    pub api_base: Option<String>,
+
+    /// Model to store in the config file
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Store the API key in the system keyring (Secret Service) instead of the config file
+    #[arg(long, requires = "api_key", conflicts_with_all = ["key_file", "key_command"])]
+    pub keyring: bool,
+
+    /// Store the API key in a passphrase-encrypted age file (*.age)
+    #[arg(long = "key-file", value_name = "PATH", requires = "api_key", conflicts_with = "key_command")]
+    pub key_file: Option<PathBuf>,
+
+    /// Command that prints the API key, e.g. "pass show openai"
+    #[arg(long = "key-command", value_name = "CMD", conflicts_with = "api_key")]
+    pub key_command: Option<String>,
+}
+
+/// API密钥的保存位置
+#[derive(Default)]
+enum KeyStore {
+    /// 明文写入配置文件
+    #[default]
+    Config,
+    Keyring,
+    File(PathBuf),
+}
+
+/// 全局参数中与 `config` 子命令相关的部分
+pub struct ConfigContext<'a> {
+    pub path: Option<&'a Path>,
+    pub provider: Option<&'a str>,
+    pub profile: Option<&'a str>,
+    pub source: Option<&'a Language>,
+    pub target: Option<&'a Language>,
+}
+
+/// 待写入配置文件的字段
+#[derive(Default)]
+struct Changes {
+    provider: Option<String>,
+    api_key: Option<String>,
+    api_base: Option<String>,
+    model: Option<String>,
+    source_language: Option<String>,
+    target_language: Option<String>,
+    key_store: KeyStore,
+    key_command: Option<String>,
+}
+
+impl Changes {
+    fn is_empty(&self) -> bool {
+        self.provider.is_none()
+            && self.api_key.is_none()
+            && self.key_command.is_none()
+            && self.api_base.is_none()
+            && self.model.is_none()
+            && self.source_language.is_none()
+            && self.target_language.is_none()
+    }
+}
+
+/// 执行 `config` 子命令
+pub async fn run(args: &ConfigArgs, ctx: &ConfigContext<'_>) -> Result<()> {
+    if args.show || args.check {
+        let config = Config::load(ctx.path)?;
+        let (profile_name, profile) = match ctx.profile.or(config.default_profile.as_deref()) {
+            Some(name) => {
+                let (name, profile) = config.profile(name)?;
+                (Some(name.to_string()), Some(profile.clone()))
+            }
+            None => (None, None),
+        };
+        let provider_name = config.provider_name(ctx.provider, profile.as_ref());
+
+        if args.show {
+            return show(&config, ctx, profile_name.as_deref(), profile.as_ref(), &provider_name);
+        }
+        return check(&config, profile.as_ref(), &provider_name).await;
+    }
+
+    let mut changes = Changes {
+        provider: ctx.provider.map(str::to_string),
+        api_key: args.api_key.clone(),
+        api_base: args.api_base.clone(),
+        model: args.model.clone(),
+        source_language: ctx.source.map(ToString::to_string),
+        target_language: ctx.target.map(ToString::to_string),
+        key_store: match (&args.key_file, args.keyring) {
+            (Some(path), _) => KeyStore::File(path.clone()),
+            (None, true) => KeyStore::Keyring,
+            (None, false) => KeyStore::Config,
+        },
+        key_command: args.key_command.clone(),
+    };
+
+    if changes.is_empty() {
+        if !io::stdin().is_terminal() {
+            return Err(anyhow!(
+                "Nothing to write. Use --provider/--api-key/--api-base/--model/-s/-t, --show or --check"
+            ));
+        }
+        changes = prompt_changes(ctx.provider)?;
+        if changes.is_empty() {
+            println!("Nothing changed.");
+            return Ok(());
+        }
+    }
+
+    if let Some(provider) = &changes.provider {
+        let spec = registry::find(provider)
+            .ok_or_else(|| anyhow!("Unknown provider: {} (run `ai-tran-cli list`)", provider))?;
+        changes.provider = Some(spec.name.to_string());
+    }
+
+    let path = match ctx.path {
+        Some(path) => path.to_path_buf(),
+        None => Config::default_path().ok_or_else(|| anyhow!("Cannot determine config directory"))?,
+    };
+    let key = store_key(&path, ctx.profile, &changes)?;
+    write(&path, ctx.profile, &changes, key.as_ref())?;
+
+    println!("Saved configuration to {}", path.display());
+    if let Some(("api_key", _)) = key {
+        println!("Note: the API key is stored in plain text; keep this file private.");
+        println!("      Use --keyring, --key-file or --key-command to keep it out of the config file.");
+    }
+    Ok(())
+}
+
+/// 按保存位置处理API密钥，返回需写入配置文件的字段
+fn store_key(path: &Path, profile: Option<&str>, changes: &Changes) -> Result<Option<(&'static str, String)>> {
+    if let Some(command) = &changes.key_command {
+        return Ok(Some(("key_command", command.clone())));
+    }
+    let Some(api_key) = &changes.api_key else {
+        return Ok(None);
+    };
+
+    match &changes.key_store {
+        KeyStore::Config => Ok(Some(("api_key", api_key.clone()))),
+        KeyStore::Keyring => {
+            // account：profile名，否则为Provider名
+            let account = match (profile, &changes.provider) {
+                (Some(profile), _) => profile.to_string(),
+                (None, Some(provider)) => provider.clone(),
+                (None, None) => {
+                    let existing = if path.exists() { Config::load(Some(path))? } else { Config::default() };
+                    existing.provider_name(None, None)
+                }
+            };
+            keys::keyring_store(&account, api_key)?;
+            println!("Stored API key in the keyring ({}/{})", keys::KEYRING_SERVICE, account);
+            Ok(Some(("keyring", account)))
+        }
+        KeyStore::File(file) => {
+            if file.extension().is_none_or(|ext| ext != "age") {
+                return Err(anyhow!("--key-file must end with .age (encrypted with an age passphrase)"));
+            }
+            keys::write_encrypted(file, api_key)?;
+            println!("Stored encrypted API key in {}", file.display());
+            Ok(Some(("key_file", file.display().to_string())))
+        }
+    }
+}
+
+/// 打印合并后的有效配置
+fn show(
+    config: &Config,
+    ctx: &ConfigContext<'_>,
+    profile_name: Option<&str>,
+    profile: Option<&Profile>,
+    provider_name: &str,
+) -> Result<()> {
+    let (spec, settings) = registry::resolve_settings(provider_name, config, profile)?;
+    let languages = config.languages(ctx.source.cloned(), ctx.target.cloned(), profile)?;
+    let path = ctx.path.map(Path::to_path_buf).or_else(Config::default_path);
+
+    // 密钥来源在此不读取，避免执行命令或提示输入口令
+    let api_key = match &settings.key_source {
+        Some(source) if settings.api_key.is_empty() => format!("(from {})", source),
+        _ if settings.api_key.is_empty() => "(not set)".to_string(),
+        _ => mask_sensitive(&settings.api_key, 7, 4),
+    };
+    let file = match &path {
+        Some(path) if path.exists() => path.display().to_string(),
+        Some(path) => format!("{} (not found, using defaults)", path.display()),
+        None => "(none)".to_string(),
+    };
+
+    println!("Config file:     {}", file);
+    println!("Profile:         {}", profile_name.unwrap_or("(none)"));
+    println!("Provider:        {}", spec.name);
+    println!("API Base:        {}", settings.api_base);
+    println!("Model:           {}", settings.model);
+    println!("API Key:         {}", api_key);
+    let fallback = config.fallback(profile);
+    if !fallback.is_empty() {
+        println!("Fallback:        {}", fallback.join(" → "));
+    }
+    println!("Source Language: {}", languages.source);
+    println!("Target Language: {}", languages.target);
+    println!("Display Mode:    {}", config.display.mode.config_name());
+    println!("Line Numbers:    {}", config.display.show_line_numbers);
+    println!(
+        "Clipboard:       auto_copy_latest={}, quick_mode_auto_copy={}",
+        config.clipboard.auto_copy_latest, config.clipboard.quick_mode_auto_copy
+    );
+    println!("Notification:    {}s", config.ui.notification_duration);
+    println!(
+        "Retry:           {} attempts, backoff {}-{}ms",
+        config.retry.max_attempts, config.retry.base_delay_ms, config.retry.max_delay_ms
+    );
+
+    if !config.profiles.is_empty() {
+        let names: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+        println!("Profiles:        {}", names.join(", "));
+    }
+    Ok(())
+}
+
+/// 发送最小测试请求，检查密钥、地址与模型
+async fn check(config: &Config, profile: Option<&Profile>, provider_name: &str) -> Result<()> {
+    let provider = registry::create_provider(provider_name, config, profile, false)?;
+    let languages = LanguagePair::new(Language::Auto, Language::Code("zh-CN".to_string()));
+
+    println!(
+        "Checking {} ({}) at {} ...",
+        provider.name(),
+        provider.model(),
+        provider.api_base()
+    );
+
+    let result = tokio::time::timeout(Duration::from_secs(30), provider.translate("Hello", &languages)).await;
+
+    match result {
+        Ok(Ok(translation)) => {
+            println!("OK: \"Hello\" → {}", translation);
+            Ok(())
+        }
+        Ok(Err(e)) => {
+            let reason = describe_error(&e);
+            Err(e.context(format!("Check failed: {}", reason)))
+        }
+        Err(_) => Err(anyhow!("Check failed: no response within 30s")),
+    }
+}
+
+/// 将请求错误归类为可读提示
+fn describe_error(error: &anyhow::Error) -> &'static str {
+    if let Some(error) = error.downcast_ref::<ProviderError>() {
+        return match error.status() {
+            Some(401 | 403) => "authentication failed, check the API key",
+            Some(404) => "endpoint or model not found, check the API base and model",
+            Some(400) if error.body().to_ascii_lowercase().contains("model") => {
+                "the model was rejected by the provider"
+            }
+            Some(429) => "rate limited or quota exceeded",
+            Some(500..=599) => "the provider returned a server error",
+            Some(_) => "the provider rejected the request",
+            None => "the connection was interrupted",
+        };
+    }
+    if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+        if error.is_connect() || error.is_timeout() {
+            return "endpoint unreachable, check the API base and network";
+        }
+    }
+    "unexpected response from the provider"
+}
+
+/// 写入配置文件，保留已有内容与注释
+///
+/// 指定profile时写入 [profiles.NAME]，否则写入 [provider] 与 [translation]
+fn write(path: &Path, profile: Option<&str>, changes: &Changes, key: Option<&(&str, String)>) -> Result<()> {
+    let mut doc = if path.exists() {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .parse::<DocumentMut>()
+            .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))?
+    } else {
+        DocumentMut::new()
+    };
+
+    if let Some(name) = profile {
+        let profiles = table_mut(doc.as_table_mut(), "profiles")?;
+        // 沿用已有profile的大小写
+        let name = profiles
+            .iter()
+            .map(|(key, _)| key.to_string())
+            .find(|key| key.eq_ignore_ascii_case(name))
+            .unwrap_or_else(|| name.to_string());
+        let table = table_mut(profiles, &name)?;
+        set(table, "provider", &changes.provider);
+        set(table, "api_base", &changes.api_base);
+        set(table, "model", &changes.model);
+        set_key(table, key);
+        set(table, "source_language", &changes.source_language);
+        set(table, "target_language", &changes.target_language);
+    } else {
+        let table = table_mut(doc.as_table_mut(), "provider")?;
+        set(table, "name", &changes.provider);
+        set(table, "api_base", &changes.api_base);
+        set(table, "model", &changes.model);
+        set_key(table, key);
+        if changes.source_language.is_some() || changes.target_language.is_some() {
+            let table = table_mut(doc.as_table_mut(), "translation")?;
+            set(table, "source_language", &changes.source_language);
+            set(table, "target_language", &changes.target_language);
+        }
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(path, doc.to_string()).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    // 配置文件可能包含密钥，仅允许当前用户读写
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+fn set(table: &mut Table, key: &str, field: &Option<String>) {
+    if let Some(field) = field {
+        replace(table, key, field);
+    }
+}
+
+/// 替换字段值，保留原有的空白与行尾注释
+fn replace(table: &mut Table, key: &str, field: &str) {
+    match table.get_mut(key).and_then(Item::as_value_mut) {
+        Some(existing) => {
+            let decor = existing.decor().clone();
+            *existing = field.into();
+            *existing.decor_mut() = decor;
+        }
+        None => table[key] = value(field),
+    }
+}
+
+/// 写入密钥字段，并移除其他密钥来源
+fn set_key(table: &mut Table, key: Option<&(&str, String)>) {
+    if let Some((field, key)) = key {
+        for other in ["api_key", "key_command", "key_file", "keyring"] {
+            if other != *field {
+                table.remove(other);
+            }
+        }
+        replace(table, field, key);
+    }
+}
+
+fn table_mut<'a>(parent: &'a mut Table, key: &str) -> Result<&'a mut Table> {
+    let item = parent.entry(key).or_insert_with(|| {
+        let mut table = Table::new();
+        table.set_implicit(key == "profiles");
+        Item::Table(table)
+    });
+    item.as_table_mut()
+        .ok_or_else(|| anyhow!("`{}` in config file is not a table", key))
+}
+
+/// 交互式设置，留空表示不修改
+fn prompt_changes(provider: Option<&str>) -> Result<Changes> {
+    let names: Vec<&str> = registry::PROVIDERS.iter().map(|spec| spec.name).collect();
+    println!("Interactive setup (press Enter to keep the current value)");
+
+    let provider = match provider {
+        Some(provider) => Some(provider.to_string()),
+        None => prompt(&format!("Provider [{}]: ", names.join("/")))?,
+    };
+    let api_key = prompt_secret("API key: ")?;
+    let key_store = match api_key {
+        Some(_) => match prompt("Store the key in [c]onfig file, [k]eyring or [a]ge file (default c): ")?.as_deref() {
+            Some("k" | "keyring") => KeyStore::Keyring,
+            Some("a" | "age") => {
+                let path = prompt("Encrypted key file (*.age): ")?
+                    .ok_or_else(|| anyhow!("No key file given"))?;
+                KeyStore::File(PathBuf::from(path))
+            }
+            _ => KeyStore::Config,
+        },
+        None => KeyStore::Config,
+    };
+
+    Ok(Changes {
+        provider,
+        api_key,
+        api_base: prompt("API base URL: ")?,
+        model: prompt("Model: ")?,
+        source_language: None,
+        target_language: prompt("Target language (e.g. zh-CN, auto): ")?
+            .map(|target| target.parse::<Language>().map(|language| language.to_string()))
+            .transpose()?,
+        key_store,
+        key_command: None,
+    })
+}
+
+fn prompt(label: &str) -> Result<Option<String>> {
+    print!("{}", label);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    let line = line.trim();
+    Ok((!line.is_empty()).then(|| line.to_string()))
+}
+
+/// 读取密钥，不回显输入
+fn prompt_secret(label: &str) -> Result<Option<String>> {
+    print!("{}", label);
+    io::stdout().flush()?;
+
+    enable_raw_mode()?;
+    let mut secret = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(anyhow!("Cancelled"))
+                }
+                KeyCode::Backspace => {
+                    secret.pop();
+                }
+                KeyCode::Char(c) => secret.push(c),
+                _ => {}
+            },
+            Ok(Event::Paste(text)) => secret.push_str(&text),
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+    disable_raw_mode()?;
+    println!();
+
+    result?;
+    let secret = secret.trim();
+    Ok((!secret.is_empty()).then(|| secret.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    fn temp_config(name: &str, contents: Option<&str>) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ai-tran-cli-test-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("config.toml");
+        if let Some(contents) = contents {
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(&path, contents).unwrap();
+        }
+        path
+    }
+
+    fn changes(provider: &str, model: &str) -> Changes {
+        Changes {
+            provider: Some(provider.to_string()),
+            model: Some(model.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn keeps_comments_and_formatting() {
+        let path = temp_config(
+            "write-comments",
+            Some("# 我的配置\n[provider]\nname = \"ollama\"   # 本地\nmodel = \"qwen\"\n\n[ui]\ntheme = \"dark\"\n"),
+        );
+        write(&path, None, &changes("openai", "gpt-4o-mini"), None).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# 我的配置\n[provider]\n"));
+        assert!(written.contains("name = \"openai\"   # 本地\n"));
+        assert!(written.contains("model = \"gpt-4o-mini\"\n"));
+        assert!(written.contains("\n[ui]\ntheme = \"dark\"\n"));
+        assert!(!written.contains("[translation]"));
+    }
+
+    #[test]
+    fn writes_the_profile_table() {
+        let path = temp_config("write-profile", Some("[provider]\nname = \"ollama\"\n\n[profiles.Work]\nmodel = \"old\"\n"));
+        write(&path, Some("work"), &changes("openai", "gpt-4o"), None).unwrap();
+        write(&path, Some("home"), &changes("deepl", "default"), None).unwrap();
+
+        let doc: DocumentMut = std::fs::read_to_string(&path).unwrap().parse().unwrap();
+        assert_eq!(doc["provider"]["name"].as_str(), Some("ollama"));
+        assert_eq!(doc["profiles"]["Work"]["provider"].as_str(), Some("openai"));
+        assert_eq!(doc["profiles"]["Work"]["model"].as_str(), Some("gpt-4o"));
+        assert_eq!(doc["profiles"]["home"]["provider"].as_str(), Some("deepl"));
+        assert!(doc["profiles"].get("work").is_none());
+    }
+
+    #[test]
+    fn creates_missing_files_and_translation_table() {
+        let path = temp_config("write-new", None);
+        let changes = Changes {
+            target_language: Some("ja".to_string()),
+            ..changes("ollama", "qwen")
+        };
+        write(&path, None, &changes, None).unwrap();
+
+        let doc: DocumentMut = std::fs::read_to_string(&path).unwrap().parse().unwrap();
+        assert_eq!(doc["provider"]["name"].as_str(), Some("ollama"));
+        assert_eq!(doc["translation"]["target_language"].as_str(), Some("ja"));
+        assert!(doc["translation"].get("source_language").is_none());
+    }
+
+    #[test]
+    fn setting_a_key_removes_other_sources() {
+        let path = temp_config(
+            "write-key",
+            Some("[provider]\nname = \"openai\"\napi_key = \"sk-old\"\nkey_file = \"~/.key\"\nkeyring = true\n"),
+        );
+        let key = ("key_command", "pass show openai".to_string());
+        write(&path, None, &Changes::default(), Some(&key)).unwrap();
+
+        let doc: DocumentMut = std::fs::read_to_string(&path).unwrap().parse().unwrap();
+        let provider = doc["provider"].as_table().unwrap();
+        assert_eq!(provider["key_command"].as_str(), Some("pass show openai"));
+        assert_eq!(provider["name"].as_str(), Some("openai"));
+        for removed in ["api_key", "key_file", "keyring"] {
+            assert!(!provider.contains_key(removed), "{} was kept", removed);
+        }
+    }
+
+    #[test]
+    fn rejects_non_table_sections() {
+        let path = temp_config("write-invalid", Some("provider = \"ollama\"\n"));
+        let error = write(&path, None, &changes("openai", "gpt-4o"), None).unwrap_err();
+        assert_eq!(error.to_string(), "`provider` in config file is not a table");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn restricts_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = temp_config("write-mode", Some("[provider]\nname = \"ollama\"\n"));
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        write(&path, None, &changes("openai", "gpt-4o"), None).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn describes_provider_errors() {
+        let describe = |status: StatusCode, body: &str| {
+            describe_error(&anyhow::Error::new(ProviderError::api(status, body.to_string())))
+        };
+        assert_eq!(describe(StatusCode::UNAUTHORIZED, ""), "authentication failed, check the API key");
+        assert_eq!(describe(StatusCode::FORBIDDEN, ""), "authentication failed, check the API key");
+        assert_eq!(
+            describe(StatusCode::NOT_FOUND, ""),
+            "endpoint or model not found, check the API base and model"
+        );
+        assert_eq!(
+            describe(StatusCode::BAD_REQUEST, "Unknown Model: foo"),
+            "the model was rejected by the provider"
+        );
+        assert_eq!(describe(StatusCode::BAD_REQUEST, "bad json"), "the provider rejected the request");
+        assert_eq!(describe(StatusCode::TOO_MANY_REQUESTS, ""), "rate limited or quota exceeded");
+        assert_eq!(describe(StatusCode::BAD_GATEWAY, ""), "the provider returned a server error");
+        assert_eq!(
+            describe_error(&anyhow::Error::new(ProviderError::Stream("eof".to_string()))),
+            "the connection was interrupted"
+        );
+        assert_eq!(describe_error(&anyhow!("other")), "unexpected response from the provider");
+    }
+
+    #[tokio::test]
+    async fn describes_unreachable_endpoints() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let error = reqwest::get(format!("http://{}", addr)).await.unwrap_err();
+        assert_eq!(
+            describe_error(&anyhow::Error::new(error)),
+            "endpoint unreachable, check the API base and network"
+        );
+    }
+}
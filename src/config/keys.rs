@@ -0,0 +1,492 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Secret Service中条目的service属性
+pub const KEYRING_SERVICE: &str = "ai-tran-cli";
+
+/// API密钥来源（配置文件中的 key_command / key_file / keyring）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeySource {
+    /// 执行外部命令，取标准输出第一行，如 `pass show openai`
+    Command(String),
+    /// 密钥文件；`.age` 结尾时用age解密（口令加密）
+    File(PathBuf),
+    /// Secret Service（通过 `secret-tool` 访问），值为account属性
+    Keyring(String),
+}
+
+impl KeySource {
+    /// 从配置字段构造，按 key_command > key_file > keyring 取第一个
+    pub fn from_fields(command: Option<&str>, file: Option<&Path>, keyring: Option<&str>) -> Option<Self> {
+        command
+            .map(|command| Self::Command(command.to_string()))
+            .or_else(|| file.map(|file| Self::File(expand_home(file))))
+            .or_else(|| keyring.map(|account| Self::Keyring(account.to_string())))
+    }
+
+    /// 读取密钥（同一来源在进程内只读取一次）
+    ///
+    /// 外部命令可能在终端上询问口令，TUI运行期间先通过 [`set_terminal_guard`] 暂时退出全屏
+    pub fn load(&self) -> Result<String> {
+        static CACHE: OnceLock<KeyCache> = OnceLock::new();
+        let guard = TERMINAL_GUARD.lock().unwrap().clone();
+        CACHE
+            .get_or_init(Default::default)
+            .load(self, &SystemBackend, guard.as_deref())
+    }
+
+    /// 通过指定后端读取密钥，取第一行（不缓存）
+    pub fn load_with(&self, backend: &dyn KeyBackend) -> Result<String> {
+        let key = match self {
+            Self::Command(command) => backend.command(command)?,
+            Self::File(path) if path.extension().is_some_and(|ext| ext == "age") => backend.decrypt(path)?,
+            Self::File(path) => read_key_file(path)?,
+            Self::Keyring(account) => backend
+                .keyring_lookup(account)
+                .map_err(|e| anyhow!("{} (is the key stored in the keyring?)", e))?,
+        };
+        let key = key.lines().next().unwrap_or_default().trim().to_string();
+        if key.is_empty() {
+            return Err(anyhow!("Empty API key from {}", self));
+        }
+        Ok(key)
+    }
+}
+
+/// 读取密钥所用的外部程序（测试中可替换）
+///
+/// Secret Service只通过 `secret-tool` 访问，测试用替身后端代替，不启动D-Bus服务
+pub trait KeyBackend {
+    /// 执行 key_command，返回标准输出
+    fn command(&self, command: &str) -> Result<String>;
+
+    /// 解密 `.age` 密钥文件
+    fn decrypt(&self, path: &Path) -> Result<String>;
+
+    /// 在Secret Service中查找account对应的密钥
+    fn keyring_lookup(&self, account: &str) -> Result<String>;
+}
+
+/// 通过 `sh -c`、`age` 与 `secret-tool` 读取
+pub struct SystemBackend;
+
+impl KeyBackend for SystemBackend {
+    fn command(&self, command: &str) -> Result<String> {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(command);
+        capture(shell, &format!("key_command `{}`", command))
+    }
+
+    fn decrypt(&self, path: &Path) -> Result<String> {
+        // age自行从终端读取口令
+        let mut age = Command::new("age");
+        age.arg("--decrypt").arg(path);
+        capture(age, "age")
+    }
+
+    fn keyring_lookup(&self, account: &str) -> Result<String> {
+        let mut lookup = Command::new("secret-tool");
+        lookup
+            .args(["lookup", "service", KEYRING_SERVICE, "account"])
+            .arg(account);
+        capture(lookup, "secret-tool lookup")
+    }
+}
+
+/// 在需要终端交互的操作前后调用：参数为true时交还终端，false时恢复
+pub type TerminalGuard = Box<dyn Fn(bool) + Send + Sync>;
+
+static TERMINAL_GUARD: Mutex<Option<Arc<TerminalGuard>>> = Mutex::new(None);
+
+/// 设置（或清除）读取密钥时使用的终端切换回调
+pub fn set_terminal_guard(guard: Option<TerminalGuard>) {
+    *TERMINAL_GUARD.lock().unwrap() = guard.map(Arc::from);
+}
+
+/// 读取过的密钥；外部命令逐个执行，避免同时在终端上询问口令
+#[derive(Default)]
+struct KeyCache {
+    keys: Mutex<HashMap<String, String>>,
+    terminal: Mutex<()>,
+}
+
+impl KeyCache {
+    /// 命中缓存直接返回，否则交还终端后通过backend读取
+    fn load(&self, source: &KeySource, backend: &dyn KeyBackend, guard: Option<&TerminalGuard>) -> Result<String> {
+        let cache_key = source.to_string();
+        if let Some(key) = self.keys.lock().unwrap().get(&cache_key) {
+            return Ok(key.clone());
+        }
+
+        blocking(|| {
+            let _terminal = self.terminal.lock().unwrap();
+            // 等待期间可能已由其他调用读到
+            if let Some(key) = self.keys.lock().unwrap().get(&cache_key) {
+                return Ok(key.clone());
+            }
+            if let Some(guard) = guard {
+                guard(true);
+            }
+            let result = source.load_with(backend);
+            if let Some(guard) = guard {
+                guard(false);
+            }
+            let key = result?;
+            self.keys.lock().unwrap().insert(cache_key.clone(), key.clone());
+            Ok(key)
+        })
+    }
+}
+
+/// 执行阻塞操作；在多线程运行时中不占用工作线程，后台翻译任务照常进行
+fn blocking<T>(f: impl FnOnce() -> T) -> T {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+impl fmt::Display for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Command(command) => write!(f, "key_command `{}`", command),
+            Self::File(path) => write!(f, "key_file {}", path.display()),
+            Self::Keyring(account) => write!(f, "keyring {}/{}", KEYRING_SERVICE, account),
+        }
+    }
+}
+
+/// 展开开头的 `~/`
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// 运行命令并返回标准输出；stdin置空，避免读走管道输入
+fn capture(mut command: Command, what: &str) -> Result<String> {
+    let output = command
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("Failed to run {}", what))?;
+
+    if !output.status.success() {
+        return Err(anyhow!("{} exited with {}", what, output.status));
+    }
+    String::from_utf8(output.stdout).map_err(|_| anyhow!("{} returned invalid UTF-8", what))
+}
+
+fn read_key_file(path: &Path) -> Result<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)
+            .with_context(|| format!("Failed to read key file {}", path.display()))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            eprintln!(
+                "Warning: key file {} is accessible by other users (chmod 600 recommended)",
+                path.display()
+            );
+        }
+    }
+
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read key file {}", path.display()))
+}
+
+/// 将密钥保存到Secret Service
+pub fn keyring_store(account: &str, key: &str) -> Result<()> {
+    let mut child = Command::new("secret-tool")
+        .args(["store", "--label"])
+        .arg(format!("{} API key ({})", KEYRING_SERVICE, account))
+        .args(["service", KEYRING_SERVICE, "account"])
+        .arg(account)
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("Failed to run secret-tool (install libsecret-tools)")?;
+
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Failed to open secret-tool stdin"))?
+        .write_all(key.as_bytes())?;
+
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow!("secret-tool store exited with {}", status));
+    }
+    Ok(())
+}
+
+/// 用age口令加密密钥并写入文件
+pub fn write_encrypted(path: &Path, key: &str) -> Result<()> {
+    let path = expand_home(path);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+
+    // age自行从终端读取口令
+    let mut child = Command::new("age")
+        .arg("--passphrase")
+        .arg("--output")
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("Failed to run age (https://age-encryption.org)")?;
+
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Failed to open age stdin"))?
+        .write_all(key.as_bytes())?;
+
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow!("age exited with {}", status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::sync::mpsc;
+
+    /// 代替外部程序与Secret Service：按名称返回预设值，并记录调用
+    #[derive(Default)]
+    struct FakeBackend {
+        secrets: HashMap<String, String>,
+        calls: RefCell<Vec<String>>,
+    }
+
+    impl FakeBackend {
+        fn with(name: &str, secret: &str) -> Self {
+            let mut backend = Self::default();
+            backend.secrets.insert(name.to_string(), secret.to_string());
+            backend
+        }
+
+        fn get(&self, call: String, name: &str, missing: &str) -> Result<String> {
+            self.calls.borrow_mut().push(call);
+            self.secrets.get(name).cloned().ok_or_else(|| anyhow!("{}", missing))
+        }
+    }
+
+    impl KeyBackend for FakeBackend {
+        fn command(&self, command: &str) -> Result<String> {
+            self.get(format!("command {}", command), command, "key_command `x` exited with exit status: 1")
+        }
+
+        fn decrypt(&self, path: &Path) -> Result<String> {
+            let path = path.display().to_string();
+            self.get(format!("decrypt {}", path), &path, "age exited with exit status: 1")
+        }
+
+        fn keyring_lookup(&self, account: &str) -> Result<String> {
+            self.get(format!("keyring {}", account), account, "secret-tool lookup exited with exit status: 1")
+        }
+    }
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ai-tran-cli-keys-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn picks_the_first_configured_source() {
+        let file = Path::new("/keys/openai.key");
+        assert_eq!(
+            KeySource::from_fields(Some("pass show openai"), Some(file), Some("openai")),
+            Some(KeySource::Command("pass show openai".to_string()))
+        );
+        assert_eq!(
+            KeySource::from_fields(None, Some(file), Some("openai")),
+            Some(KeySource::File(file.to_path_buf()))
+        );
+        assert_eq!(
+            KeySource::from_fields(None, None, Some("openai")),
+            Some(KeySource::Keyring("openai".to_string()))
+        );
+        assert_eq!(KeySource::from_fields(None, None, None), None);
+    }
+
+    #[test]
+    fn expands_home_in_key_files() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        let source = KeySource::from_fields(None, Some(Path::new("~/keys/a.age")), None);
+        assert_eq!(source, Some(KeySource::File(home.join("keys/a.age"))));
+    }
+
+    #[test]
+    fn describes_each_source() {
+        assert_eq!(KeySource::Command("pass show x".to_string()).to_string(), "key_command `pass show x`");
+        assert_eq!(KeySource::File(PathBuf::from("/k/a.age")).to_string(), "key_file /k/a.age");
+        assert_eq!(KeySource::Keyring("openai".to_string()).to_string(), "keyring ai-tran-cli/openai");
+    }
+
+    #[test]
+    fn reads_the_first_line_of_command_output() {
+        let backend = FakeBackend::with("pass show openai", "  sk-command  \nmetadata: x\n");
+        let source = KeySource::Command("pass show openai".to_string());
+        assert_eq!(source.load_with(&backend).unwrap(), "sk-command");
+        assert_eq!(*backend.calls.borrow(), vec!["command pass show openai"]);
+    }
+
+    #[test]
+    fn decrypts_age_files_with_the_backend() {
+        let backend = FakeBackend::with("/keys/openai.key.age", "sk-age\n");
+        let source = KeySource::File(PathBuf::from("/keys/openai.key.age"));
+        assert_eq!(source.load_with(&backend).unwrap(), "sk-age");
+        assert_eq!(*backend.calls.borrow(), vec!["decrypt /keys/openai.key.age"]);
+    }
+
+    #[test]
+    fn reads_plain_key_files_directly() {
+        let path = temp_file("plain.key", "sk-file\n");
+        let backend = FakeBackend::default();
+        assert_eq!(KeySource::File(path).load_with(&backend).unwrap(), "sk-file");
+        assert!(backend.calls.borrow().is_empty());
+
+        let missing = KeySource::File(PathBuf::from("/nonexistent/ai-tran-cli.key"));
+        let error = missing.load_with(&backend).unwrap_err();
+        assert_eq!(error.to_string(), "Failed to read key file /nonexistent/ai-tran-cli.key");
+    }
+
+    #[test]
+    fn looks_up_keyring_entries() {
+        let backend = FakeBackend::with("openai", "sk-keyring");
+        assert_eq!(KeySource::Keyring("openai".to_string()).load_with(&backend).unwrap(), "sk-keyring");
+
+        let error = KeySource::Keyring("other".to_string()).load_with(&backend).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "secret-tool lookup exited with exit status: 1 (is the key stored in the keyring?)"
+        );
+    }
+
+    #[test]
+    fn rejects_empty_keys() {
+        let backend = FakeBackend::with("true", "\n");
+        let error = KeySource::Command("true".to_string()).load_with(&backend).unwrap_err();
+        assert_eq!(error.to_string(), "Empty API key from key_command `true`");
+
+        let path = temp_file("empty.key", "");
+        let error = KeySource::File(path.clone()).load_with(&backend).unwrap_err();
+        assert_eq!(error.to_string(), format!("Empty API key from key_file {}", path.display()));
+    }
+
+    #[test]
+    fn propagates_backend_errors() {
+        let error = KeySource::Command("x".to_string()).load_with(&FakeBackend::default()).unwrap_err();
+        assert_eq!(error.to_string(), "key_command `x` exited with exit status: 1");
+    }
+
+    /// 记录交还（true）与恢复（false）终端的回调
+    fn recording_guard() -> (TerminalGuard, Arc<Mutex<Vec<bool>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let guard: TerminalGuard = Box::new({
+            let events = Arc::clone(&events);
+            move |suspend| events.lock().unwrap().push(suspend)
+        });
+        (guard, events)
+    }
+
+    #[test]
+    fn hands_the_terminal_over_while_loading() {
+        let cache = KeyCache::default();
+        let backend = FakeBackend::with("pass show openai", "sk-guarded");
+        let (guard, events) = recording_guard();
+        let source = KeySource::Command("pass show openai".to_string());
+
+        assert_eq!(cache.load(&source, &backend, Some(&guard)).unwrap(), "sk-guarded");
+        assert_eq!(cache.load(&source, &backend, Some(&guard)).unwrap(), "sk-guarded");
+        // 第二次读取命中缓存，不再调用后端或交还终端
+        assert_eq!(backend.calls.borrow().len(), 1);
+        assert_eq!(*events.lock().unwrap(), vec![true, false]);
+    }
+
+    #[test]
+    fn restores_the_terminal_and_retries_after_errors() {
+        let cache = KeyCache::default();
+        let backend = FakeBackend::default();
+        let (guard, events) = recording_guard();
+        let source = KeySource::Keyring("openai".to_string());
+
+        assert!(cache.load(&source, &backend, Some(&guard)).is_err());
+        assert!(cache.load(&source, &backend, Some(&guard)).is_err());
+        assert_eq!(backend.calls.borrow().len(), 2);
+        assert_eq!(*events.lock().unwrap(), vec![true, false, true, false]);
+    }
+
+    /// 读取时等待测试放行的后端
+    struct BlockingBackend {
+        started: Mutex<mpsc::Sender<()>>,
+        release: Mutex<mpsc::Receiver<()>>,
+        calls: Mutex<usize>,
+    }
+
+    impl KeyBackend for BlockingBackend {
+        fn command(&self, _command: &str) -> Result<String> {
+            *self.calls.lock().unwrap() += 1;
+            self.started.lock().unwrap().send(()).unwrap();
+            self.release.lock().unwrap().recv().unwrap();
+            Ok("sk-slow".to_string())
+        }
+
+        fn decrypt(&self, _path: &Path) -> Result<String> {
+            unreachable!()
+        }
+
+        fn keyring_lookup(&self, _account: &str) -> Result<String> {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn cached_keys_do_not_wait_for_a_running_command() {
+        let cache = Arc::new(KeyCache::default());
+        let cached = KeySource::Command("cached".to_string());
+        cache.load(&cached, &FakeBackend::with("cached", "sk-cached"), None).unwrap();
+
+        let (started, on_start) = mpsc::channel();
+        let (release, on_release) = mpsc::channel();
+        let backend = Arc::new(BlockingBackend {
+            started: Mutex::new(started),
+            release: Mutex::new(on_release),
+            calls: Mutex::new(0),
+        });
+        let slow = KeySource::Command("slow".to_string());
+        let loaders: Vec<_> = (0..2)
+            .map(|_| {
+                let (cache, backend, slow) = (Arc::clone(&cache), Arc::clone(&backend), slow.clone());
+                std::thread::spawn(move || cache.load(&slow, backend.as_ref(), None).unwrap())
+            })
+            .collect();
+        on_start.recv().unwrap();
+
+        // 外部命令仍在运行时，已缓存的密钥照常返回
+        assert_eq!(cache.load(&cached, &FakeBackend::default(), None).unwrap(), "sk-cached");
+        release.send(()).unwrap();
+        for loader in loaders {
+            assert_eq!(loader.join().unwrap(), "sk-slow");
+        }
+        // 同时读取同一来源只执行一次命令
+        assert_eq!(*backend.calls.lock().unwrap(), 1);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod command;
+pub mod keys;
+pub mod settings;
+
+pub use settings::{Config, Profile};
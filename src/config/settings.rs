@@ -0,0 +1,624 @@
+use super::keys::KeySource;
+use crate::app::DisplayMode;
+use crate::providers::{registry::ProviderSettings, Language, LanguagePair};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// 配置文件（~/.config/ai-tran-cli/config.toml）
+///
+/// 优先级：命令行参数 > profile > 环境变量 > 配置文件 > 默认值
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// 未指定 --profile 时使用的profile
+    pub default_profile: Option<String>,
+    pub profiles: BTreeMap<String, Profile>,
+    pub provider: ProviderConfig,
+    pub translation: TranslationConfig,
+    pub display: DisplayConfig,
+    pub clipboard: ClipboardConfig,
+    pub ui: UiConfig,
+    pub retry: RetryConfig,
+    pub compare: CompareConfig,
+    pub history: HistoryConfig,
+    pub document: DocumentConfig,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ProviderConfig {
+    pub name: Option<String>,
+    #[serde(alias = "endpoint")]
+    pub api_base: Option<String>,
+    pub model: Option<String>,
+    pub api_key: Option<String>,
+    /// 执行命令获取API密钥，如 `pass show openai`
+    pub key_command: Option<String>,
+    /// API密钥文件（`.age` 为口令加密文件）
+    pub key_file: Option<PathBuf>,
+    /// Secret Service中的account名
+    pub keyring: Option<String>,
+    /// 出错时依次尝试的profile名或Provider名
+    pub fallback: Vec<String>,
+}
+
+impl ProviderConfig {
+    pub fn key_source(&self) -> Option<KeySource> {
+        KeySource::from_fields(
+            self.key_command.as_deref(),
+            self.key_file.as_deref(),
+            self.keyring.as_deref(),
+        )
+    }
+}
+
+/// 命名profile：打包provider、连接参数、密钥来源和语言对
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub provider: Option<String>,
+    #[serde(alias = "endpoint")]
+    pub api_base: Option<String>,
+    pub model: Option<String>,
+    pub api_key: Option<String>,
+    /// 从指定环境变量读取API密钥
+    pub api_key_env: Option<String>,
+    pub key_command: Option<String>,
+    pub key_file: Option<PathBuf>,
+    pub keyring: Option<String>,
+    pub fallback: Option<Vec<String>>,
+    pub source_language: Option<String>,
+    pub target_language: Option<String>,
+}
+
+impl Profile {
+    pub fn key_source(&self) -> Option<KeySource> {
+        KeySource::from_fields(
+            self.key_command.as_deref(),
+            self.key_file.as_deref(),
+            self.keyring.as_deref(),
+        )
+    }
+
+    /// 用profile中显式设置的字段覆盖连接参数
+    ///
+    /// 密钥优先级：api_key_env > key_command/key_file/keyring > api_key
+    pub fn apply(&self, settings: &mut ProviderSettings) -> Result<()> {
+        if let Some(api_base) = &self.api_base {
+            settings.api_base = api_base.clone();
+        }
+        if let Some(model) = &self.model {
+            settings.model = model.clone();
+        }
+        if let Some(key_env) = &self.api_key_env {
+            settings.api_key = std::env::var(key_env)
+                .ok()
+                .filter(|key| !key.trim().is_empty())
+                .ok_or_else(|| anyhow!("{} not found in environment", key_env))?;
+            settings.key_source = None;
+        } else if let Some(source) = self.key_source() {
+            settings.api_key.clear();
+            settings.key_source = Some(source);
+        } else if let Some(api_key) = &self.api_key {
+            settings.api_key = api_key.clone();
+            settings.key_source = None;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TranslationConfig {
+    pub target_language: Option<String>,
+    pub source_language: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    pub mode: DisplayMode,
+    pub show_line_numbers: bool,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            mode: DisplayMode::Bilingual,
+            show_line_numbers: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    pub auto_copy_latest: bool,
+    pub quick_mode_auto_copy: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// 通知显示时长（秒）
+    pub notification_duration: u64,
+    /// 保存的输入历史条数（0表示不记录）
+    pub input_history_size: usize,
+    /// 捕获鼠标以支持滚轮滚动（会占用终端的文字选择，可用Alt+M切换）
+    pub mouse: bool,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            notification_duration: 3,
+            input_history_size: 1000,
+            mouse: false,
+        }
+    }
+}
+
+/// 翻译历史（数据目录下的 history.jsonl）
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// 记录每次翻译
+    pub enabled: bool,
+    /// 启动TUI时载入上次会话
+    pub resume: bool,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            resume: false,
+        }
+    }
+}
+
+/// 长文档分块翻译
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct DocumentConfig {
+    /// 每块的估算token上限，超过时分块翻译（0为不分块）
+    pub chunk_tokens: usize,
+    /// 同时翻译的块数
+    pub concurrency: usize,
+    /// 附带前文以保持术语一致（顺序翻译时为上一块的译文，并发时为上一块的原文）
+    pub context: bool,
+}
+
+impl Default for DocumentConfig {
+    fn default() -> Self {
+        Self {
+            chunk_tokens: 1500,
+            concurrency: 1,
+            context: true,
+        }
+    }
+}
+
+/// 对比模式：同一输入并行发给多个Provider
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CompareConfig {
+    /// profile名、Provider名或 `provider:model`
+    pub providers: Vec<String>,
+}
+
+/// 请求失败时的重试策略
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// 总尝试次数（1表示不重试）
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 8000,
+        }
+    }
+}
+
+impl Config {
+    /// 按名称查找profile（不区分大小写），返回配置中的名称与profile
+    pub fn profile(&self, name: &str) -> Result<(&str, &Profile)> {
+        let found = self
+            .profiles
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(key, profile)| (key.as_str(), profile));
+
+        found.ok_or_else(|| {
+            let available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            if available.is_empty() {
+                anyhow!("Unknown profile: {} (no profiles defined in config file)", name)
+            } else {
+                anyhow!("Unknown profile: {} (available: {})", name, available.join(", "))
+            }
+        })
+    }
+
+    /// Provider名称：命令行 > profile > [provider].name > openai
+    pub fn provider_name(&self, cli: Option<&str>, profile: Option<&Profile>) -> String {
+        cli.map(str::to_string)
+            .or_else(|| profile.and_then(|p| p.provider.clone()))
+            .or_else(|| self.provider.name.clone())
+            .unwrap_or_else(|| crate::providers::registry::DEFAULT_PROVIDER.to_string())
+    }
+
+    /// fallback链：profile > [provider].fallback
+    pub fn fallback(&self, profile: Option<&Profile>) -> Vec<String> {
+        profile
+            .and_then(|p| p.fallback.clone())
+            .unwrap_or_else(|| self.provider.fallback.clone())
+    }
+
+    /// 语言对：命令行 > profile > 环境变量 > 配置文件 > auto
+    pub fn languages(
+        &self,
+        source: Option<Language>,
+        target: Option<Language>,
+        profile: Option<&Profile>,
+    ) -> Result<LanguagePair> {
+        Ok(LanguagePair::new(
+            resolve_language(
+                source,
+                profile.and_then(|p| p.source_language.as_deref()),
+                "SOURCE_LANGUAGE",
+                self.translation.source_language.as_deref(),
+            )?,
+            resolve_language(
+                target,
+                profile.and_then(|p| p.target_language.as_deref()),
+                "TARGET_LANGUAGE",
+                self.translation.target_language.as_deref(),
+            )?,
+        ))
+    }
+
+    /// 默认配置文件路径：$XDG_CONFIG_HOME/ai-tran-cli/config.toml 或 ~/.config/ai-tran-cli/config.toml
+    pub fn default_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join(".config")))?;
+        Some(base.join("ai-tran-cli").join("config.toml"))
+    }
+
+    /// 数据目录：$XDG_DATA_HOME/ai-tran-cli 或 ~/.local/share/ai-tran-cli
+    pub fn data_dir() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join(".local").join("share")))?;
+        Some(base.join("ai-tran-cli"))
+    }
+
+    /// 加载配置文件；显式指定的路径必须存在，默认路径不存在时使用默认值
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Self::default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        if !required && !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let settings = config::Config::builder()
+            .add_source(
+                config::File::from(path.as_path())
+                    .format(config::FileFormat::Toml)
+                    .required(required),
+            )
+            .build()
+            .map_err(|e| anyhow!("Failed to load config file {}: {}", path.display(), e))?;
+
+        settings
+            .try_deserialize()
+            .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))
+    }
+}
+
+fn resolve_language(
+    cli: Option<Language>,
+    profile: Option<&str>,
+    env_key: &str,
+    config: Option<&str>,
+) -> Result<Language> {
+    if let Some(language) = cli {
+        return Ok(language);
+    }
+    if let Some(value) = profile {
+        return value
+            .parse()
+            .map_err(|e| anyhow!("Invalid language in profile: {}", e));
+    }
+    if let Ok(value) = std::env::var(env_key) {
+        if !value.trim().is_empty() {
+            return value
+                .parse()
+                .map_err(|e| anyhow!("Invalid {}: {}", env_key, e));
+        }
+    }
+    match config {
+        Some(value) => value
+            .parse()
+            .map_err(|e| anyhow!("Invalid language in config file: {}", e)),
+        None => Ok(Language::Auto),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::registry;
+    use std::sync::{Mutex, MutexGuard};
+
+    /// 修改环境变量的测试须串行执行
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    /// 测试期间设置（None为删除）环境变量，结束时恢复原值
+    struct Env {
+        saved: Vec<(&'static str, Option<String>)>,
+        _lock: MutexGuard<'static, ()>,
+    }
+
+    impl Env {
+        fn set(vars: &[(&'static str, Option<&str>)]) -> Self {
+            let lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let mut saved = Vec::new();
+            for &(key, value) in vars {
+                saved.push((key, std::env::var(key).ok()));
+                match value {
+                    Some(value) => std::env::set_var(key, value),
+                    None => std::env::remove_var(key),
+                }
+            }
+            Self { saved, _lock: lock }
+        }
+    }
+
+    impl Drop for Env {
+        fn drop(&mut self) {
+            for (key, value) in &self.saved {
+                match value {
+                    Some(value) => std::env::set_var(key, value),
+                    None => std::env::remove_var(key),
+                }
+            }
+        }
+    }
+
+    /// 写入测试用配置文件
+    fn write_config(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ai-tran-cli-test-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    const FILE: &str = r#"
+[provider]
+name = "gemini"
+model = "file-model"
+api_base = "http://file.example/v1beta"
+
+[translation]
+source_language = "en"
+target_language = "ja"
+
+[ui]
+input_history_size = 10
+"#;
+
+    fn language(code: &str) -> Language {
+        code.parse().unwrap()
+    }
+
+    fn clear_env() -> Env {
+        Env::set(&[
+            ("SOURCE_LANGUAGE", None),
+            ("TARGET_LANGUAGE", None),
+            ("GEMINI_MODEL", None),
+            ("GEMINI_API_BASE", None),
+        ])
+    }
+
+    #[test]
+    fn file_values_override_defaults() {
+        let _env = clear_env();
+        let config = Config::load(Some(&write_config("file", FILE))).unwrap();
+
+        assert_eq!(config.languages(None, None, None).unwrap(), LanguagePair::new(language("en"), language("ja")));
+        assert_eq!(config.provider_name(None, None), "gemini");
+        assert_eq!(config.ui.input_history_size, 10);
+        // 未出现的字段取默认值
+        assert_eq!(config.ui.notification_duration, 3);
+
+        let (_, settings) = registry::resolve_settings("gemini", &config, None).unwrap();
+        assert_eq!(settings.model, "file-model");
+        assert_eq!(settings.api_base, "http://file.example/v1beta");
+    }
+
+    #[test]
+    fn env_overrides_file_and_cli_overrides_env() {
+        let _env = Env::set(&[
+            ("SOURCE_LANGUAGE", None),
+            ("TARGET_LANGUAGE", Some("de")),
+            ("GEMINI_MODEL", Some("env-model")),
+            ("GEMINI_API_BASE", Some("http://env.example/v1beta")),
+        ]);
+        let config = Config::load(Some(&write_config("env", FILE))).unwrap();
+
+        let languages = config.languages(None, None, None).unwrap();
+        assert_eq!(languages, LanguagePair::new(language("en"), language("de")));
+        let languages = config.languages(Some(Language::Auto), Some(language("fr")), None).unwrap();
+        assert_eq!(languages, LanguagePair::new(Language::Auto, language("fr")));
+
+        let (_, settings) = registry::resolve_settings("gemini", &config, None).unwrap();
+        assert_eq!(settings.model, "env-model");
+        assert_eq!(settings.api_base, "http://env.example/v1beta");
+        assert_eq!(config.provider_name(Some("ollama"), None), "ollama");
+    }
+
+    #[test]
+    fn defaults_without_file_or_env() {
+        let _env = clear_env();
+        let config = Config::default();
+
+        assert_eq!(config.languages(None, None, None).unwrap(), LanguagePair::default());
+        assert_eq!(config.provider_name(None, None), registry::DEFAULT_PROVIDER);
+        let (_, settings) = registry::resolve_settings("gemini", &config, None).unwrap();
+        assert_eq!(settings.model, "gemini-2.5-flash");
+    }
+
+    #[test]
+    fn file_settings_of_another_provider_are_ignored() {
+        let _env = clear_env();
+        let config = Config::load(Some(&write_config("other", FILE))).unwrap();
+        let (_, settings) = registry::resolve_settings("ollama", &config, None).unwrap();
+        assert_ne!(settings.model, "file-model");
+    }
+
+    #[test]
+    fn reports_invalid_values() {
+        let _env = Env::set(&[("SOURCE_LANGUAGE", None), ("TARGET_LANGUAGE", Some("not a code"))]);
+        let error = Config::default().languages(None, None, None).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid TARGET_LANGUAGE: Invalid language code: not a code");
+
+        let config = Config::load(Some(&write_config("bad-language", "[translation]\nsource_language = \"x y\"\n"))).unwrap();
+        let error = config.languages(None, Some(Language::Auto), None).unwrap_err();
+        assert!(error.to_string().starts_with("Invalid language in config file"));
+    }
+
+    #[test]
+    fn loading_fails_for_missing_or_invalid_files() {
+        let missing = std::env::temp_dir().join("ai-tran-cli-test-missing").join("config.toml");
+        assert!(Config::load(Some(&missing)).unwrap_err().to_string().starts_with("Failed to load config file"));
+
+        let invalid = write_config("invalid", "[ui]\nnotification_duration = \"soon\"\n");
+        assert!(Config::load(Some(&invalid)).unwrap_err().to_string().starts_with("Invalid config file"));
+    }
+
+    const PROFILES: &str = r#"
+[provider]
+name = "gemini"
+model = "file-model"
+
+[translation]
+target_language = "ja"
+
+[profiles.Work]
+provider = "gemini"
+model = "profile-model"
+api_base = "http://profile.example/v1beta"
+api_key_env = "AI_TRAN_TEST_PROFILE_KEY"
+source_language = "en"
+target_language = "ko"
+
+[profiles.vault]
+key_command = "pass show gemini"
+api_key = "ignored"
+
+[profiles.local]
+provider = "ollama"
+model = "qwen"
+"#;
+
+    #[test]
+    fn profile_overrides_file_and_env() {
+        let _env = Env::set(&[
+            ("SOURCE_LANGUAGE", Some("fr")),
+            ("TARGET_LANGUAGE", Some("de")),
+            ("GEMINI_MODEL", Some("env-model")),
+            ("GEMINI_API_BASE", Some("http://env.example/v1beta")),
+            ("AI_TRAN_TEST_PROFILE_KEY", Some("profile-key")),
+        ]);
+        let config = Config::load(Some(&write_config("profile", PROFILES))).unwrap();
+        let (_, profile) = config.profile("work").unwrap();
+
+        let languages = config.languages(None, None, Some(profile)).unwrap();
+        assert_eq!(languages, LanguagePair::new(language("en"), language("ko")));
+
+        let (_, settings) = registry::resolve_settings("gemini", &config, Some(profile)).unwrap();
+        assert_eq!(settings.model, "profile-model");
+        assert_eq!(settings.api_base, "http://profile.example/v1beta");
+        assert_eq!(settings.api_key, "profile-key");
+        assert!(settings.key_source.is_none());
+    }
+
+    #[test]
+    fn cli_flags_override_profile() {
+        let _env = clear_env();
+        let config = Config::load(Some(&write_config("profile-cli", PROFILES))).unwrap();
+        let (_, profile) = config.profile("Work").unwrap();
+
+        let languages = config.languages(Some(language("zh")), Some(language("fr")), Some(profile)).unwrap();
+        assert_eq!(languages, LanguagePair::new(language("zh"), language("fr")));
+        assert_eq!(config.provider_name(None, Some(profile)), "gemini");
+        assert_eq!(config.provider_name(Some("ollama"), Some(profile)), "ollama");
+
+        // -p 选了其他Provider时，profile的连接参数不适用
+        let (_, settings) = registry::resolve_settings("ollama", &config, Some(profile)).unwrap();
+        assert_ne!(settings.model, "profile-model");
+    }
+
+    #[test]
+    fn profile_key_sources() {
+        let _env = Env::set(&[("AI_TRAN_TEST_PROFILE_KEY", None), ("GEMINI_MODEL", None)]);
+        let config = Config::load(Some(&write_config("profile-keys", PROFILES))).unwrap();
+
+        let (_, work) = config.profile("work").unwrap();
+        let Err(error) = registry::resolve_settings("gemini", &config, Some(work)) else {
+            panic!("expected a missing key error");
+        };
+        assert_eq!(error.to_string(), "AI_TRAN_TEST_PROFILE_KEY not found in environment");
+
+        // 密钥来源优先于profile中的api_key
+        let (_, vault) = config.profile("vault").unwrap();
+        let (_, settings) = registry::resolve_settings("gemini", &config, Some(vault)).unwrap();
+        assert!(settings.api_key.is_empty());
+        assert_eq!(settings.key_source, Some(KeySource::Command("pass show gemini".to_string())));
+        // 未设置的字段沿用配置文件
+        assert_eq!(settings.model, "file-model");
+    }
+
+    #[test]
+    fn unknown_profile_is_an_error() {
+        let config = Config::load(Some(&write_config("profile-unknown", PROFILES))).unwrap();
+        assert_eq!(config.profile("LOCAL").unwrap().0, "local");
+        assert_eq!(
+            config.profile("home").unwrap_err().to_string(),
+            "Unknown profile: home (available: local, vault, work)"
+        );
+        assert_eq!(
+            Config::default().profile("home").unwrap_err().to_string(),
+            "Unknown profile: home (no profiles defined in config file)"
+        );
+    }
+
+    #[test]
+    fn default_path_follows_xdg_config_home() {
+        let path = write_config("xdg/ai-tran-cli", FILE);
+        let home = path.parent().unwrap().parent().unwrap().to_str().unwrap().to_string();
+        let _env = Env::set(&[("XDG_CONFIG_HOME", Some(&home)), ("GEMINI_MODEL", None)]);
+
+        assert_eq!(Config::default_path().unwrap(), path);
+        assert_eq!(Config::load(None).unwrap().provider_name(None, None), "gemini");
+    }
+}
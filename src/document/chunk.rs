@@ -0,0 +1,323 @@
+use crate::providers::prompt::{placeholder, PLACEHOLDER_CLOSE, PLACEHOLDER_OPEN};
+use unicode_width::UnicodeWidthChar;
+
+/// 文档中的一块：待翻译文本与其后原样保留的内容
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Chunk {
+    pub text: String,
+    pub separator: String,
+    /// text中 ⟦N⟧ 占位符对应的原文（代码、链接地址等）
+    pub protected: Vec<String>,
+    /// 合并多段时，段落之间结构（换行、标题井号等）的占位符序号
+    pub breaks: Vec<usize>,
+    /// 合并前的各段（separator为其后的结构），整块还原失败时逐段重译
+    pub segments: Vec<Chunk>,
+}
+
+impl Chunk {
+    /// 把译文中的占位符换回原文；占位符缺失、重复、无法识别或段落顺序错乱时返回None
+    pub fn restore(&self, translation: &str) -> Option<String> {
+        let translation = translation.trim();
+        if self.protected.is_empty() {
+            return Some(translation.to_string());
+        }
+
+        let mut used = vec![false; self.protected.len()];
+        let mut last_break = None;
+        let mut result = String::new();
+        let mut rest = translation;
+        while let Some(open) = rest.find(PLACEHOLDER_OPEN) {
+            let after = &rest[open + PLACEHOLDER_OPEN.len_utf8()..];
+            let close = after.find(PLACEHOLDER_CLOSE)?;
+            let index = after[..close]
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|&index| index < self.protected.len() && !used[index])?;
+            used[index] = true;
+            let after = &after[close + PLACEHOLDER_CLOSE.len_utf8()..];
+
+            if self.breaks.contains(&index) {
+                // 段落间的结构自带换行，去掉模型在两侧加的空白
+                if last_break.is_some_and(|last| last > index) {
+                    return None;
+                }
+                last_break = Some(index);
+                result.push_str(rest[..open].trim_end());
+                result.push_str(&self.protected[index]);
+                rest = after.trim_start();
+            } else {
+                result.push_str(&rest[..open]);
+                result.push_str(&self.protected[index]);
+                rest = after;
+            }
+        }
+        result.push_str(rest);
+        used.iter().all(|&used| used).then_some(result)
+    }
+
+    /// 未翻译的原文（占位符换回原文）
+    pub fn original(&self) -> String {
+        self.restore(&self.text).unwrap_or_else(|| self.text.clone())
+    }
+
+    /// 把span作为占位符追加到text
+    pub fn protect(&mut self, span: &str) {
+        self.text.push_str(&placeholder(self.protected.len()));
+        self.protected.push(span.to_string());
+    }
+
+    /// 把下一段并入本块：本块的separator变为段落间的占位符，下一段的占位符重新编号
+    pub fn append(&mut self, next: Chunk) {
+        if self.segments.is_empty() {
+            self.segments.push(self.clone());
+        }
+        let separator = std::mem::take(&mut self.separator);
+        self.breaks.push(self.protected.len());
+        self.protect(&separator);
+
+        let offset = self.protected.len();
+        let mut rest = next.text.as_str();
+        while let Some(open) = rest.find(PLACEHOLDER_OPEN) {
+            self.text.push_str(&rest[..open]);
+            let after = &rest[open + PLACEHOLDER_OPEN.len_utf8()..];
+            let close = after.find(PLACEHOLDER_CLOSE).unwrap_or(after.len());
+            match after[..close].parse::<usize>() {
+                Ok(index) => self.text.push_str(&placeholder(offset + index)),
+                Err(_) => self.text.push_str(&rest[open..open + PLACEHOLDER_OPEN.len_utf8() + close]),
+            }
+            rest = after.get(close + PLACEHOLDER_CLOSE.len_utf8()..).unwrap_or("");
+        }
+        self.text.push_str(rest);
+        self.protected.extend(next.protected.iter().cloned());
+        self.separator = next.separator.clone();
+        self.segments.push(next);
+    }
+}
+
+/// 粗略估算token数：全角字符每个约1 token，其余约4个字符1 token
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut count = TokenCount::default();
+    text.chars().for_each(|c| count.add(c));
+    count.tokens()
+}
+
+/// 逐字符累计的token估算
+#[derive(Clone, Copy, Default)]
+struct TokenCount {
+    wide: usize,
+    narrow: usize,
+}
+
+impl TokenCount {
+    fn add(&mut self, c: char) {
+        if c.width() == Some(2) {
+            self.wide += 1;
+        } else {
+            self.narrow += 1;
+        }
+    }
+
+    fn tokens(&self) -> usize {
+        self.wide + self.narrow.div_ceil(4)
+    }
+}
+
+/// 按段落、句子边界切分为不超过max_tokens的块；拼接 text + separator 可还原原文（首尾空白除外）
+pub fn split(text: &str, max_tokens: usize) -> Vec<Chunk> {
+    let max_tokens = max_tokens.max(1);
+    let mut pieces = Vec::new();
+    for (paragraph, separator) in paragraphs(text.trim()) {
+        if estimate_tokens(paragraph) <= max_tokens {
+            pieces.push((paragraph, separator));
+            continue;
+        }
+        let sentences = sentences(paragraph);
+        let last = sentences.len() - 1;
+        for (index, (sentence, space)) in sentences.into_iter().enumerate() {
+            let space = if index == last { separator } else { space };
+            if estimate_tokens(sentence) <= max_tokens {
+                pieces.push((sentence, space));
+            } else {
+                let mut parts = hard_split(sentence, max_tokens);
+                if let Some(last) = parts.last_mut() {
+                    last.1 = space;
+                }
+                pieces.extend(parts);
+            }
+        }
+    }
+
+    // 贪心合并相邻片段
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let mut current = Chunk::default();
+    let mut tokens = 0;
+    for (piece, separator) in pieces {
+        let piece_tokens = estimate_tokens(piece);
+        if !current.text.is_empty() && tokens + piece_tokens > max_tokens {
+            chunks.push(std::mem::take(&mut current));
+            tokens = 0;
+        }
+        current.text.push_str(&current.separator);
+        current.text.push_str(piece);
+        current.separator = separator.to_string();
+        tokens += piece_tokens + estimate_tokens(separator);
+    }
+    if !current.text.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// 以空行分段，返回 (段落, 其后的空白)
+fn paragraphs(text: &str) -> Vec<(&str, &str)> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    let mut content_end = None;
+
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            // 空行：当前段落到此结束
+            if content_end.is_none() {
+                content_end = Some(offset);
+            }
+        } else if let Some(end) = content_end.take() {
+            if end > start {
+                result.push((text[start..end].trim_end(), &text[text[..end].trim_end().len()..offset]));
+            }
+            start = offset;
+        }
+        offset += line.len();
+    }
+    let end = content_end.unwrap_or(text.len());
+    result.push((text[start..end].trim_end(), &text[text[..end].trim_end().len()..]));
+    result
+}
+
+/// 在句末标点后切分，返回 (句子, 其后的空白)
+fn sentences(paragraph: &str) -> Vec<(&str, &str)> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut chars = paragraph.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let wide = matches!(c, '。' | '！' | '？' | '；');
+        if !wide && !matches!(c, '.' | '!' | '?' | ';') {
+            continue;
+        }
+        let end = i + c.len_utf8();
+        let mut space_end = end;
+        while let Some(&(j, next)) = chars.peek() {
+            if !next.is_whitespace() {
+                break;
+            }
+            space_end = j + next.len_utf8();
+            chars.next();
+        }
+        // 半角标点后须有空白才算句末（避免切开 3.14、e.g. 等）
+        if (wide || space_end > end) && space_end < paragraph.len() {
+            result.push((&paragraph[start..end], &paragraph[end..space_end]));
+            start = space_end;
+        }
+    }
+    result.push((&paragraph[start..], ""));
+    result
+}
+
+/// 没有合适边界的超长句强制切分，尽量断在空白处，返回 (片段, 其后的空白)
+fn hard_split(text: &str, max_tokens: usize) -> Vec<(&str, &str)> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    // 当前片段中最后一段空白的范围
+    let mut space: Option<(usize, usize)> = None;
+    let mut count = TokenCount::default();
+
+    for (i, c) in text.char_indices() {
+        let end = i + c.len_utf8();
+        count.add(c);
+        if c.is_whitespace() {
+            space = match space {
+                Some((from, to)) if to == i => Some((from, end)),
+                _ => Some((i, end)),
+            };
+        } else if count.tokens() > max_tokens && i > start {
+            match space.take() {
+                Some((from, to)) if from > start => {
+                    parts.push((&text[start..from], &text[from..to]));
+                    start = to;
+                }
+                _ => {
+                    parts.push((&text[start..i], ""));
+                    start = i;
+                }
+            }
+            count = TokenCount::default();
+            text[start..end].chars().for_each(|c| count.add(c));
+        }
+    }
+    parts.push((&text[start..], ""));
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn join(chunks: &[Chunk]) -> String {
+        chunks.iter().map(|chunk| format!("{}{}", chunk.text, chunk.separator)).collect()
+    }
+
+    #[test]
+    fn estimates_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        assert_eq!(estimate_tokens("你好"), 2);
+        assert_eq!(estimate_tokens("你好 ab"), 3);
+    }
+
+    #[test]
+    fn split_round_trips() {
+        let text = "First paragraph. It has two sentences.\n\n\nSecond paragraph is here!\r\n\r\n你好。世界！还有第三句。\n\nA very long sentence without any punctuation that goes on and on and on\n";
+        for max_tokens in [1, 3, 5, 10, 20, 1000] {
+            let chunks = split(text, max_tokens);
+            assert_eq!(join(&chunks), text.trim(), "max_tokens = {}", max_tokens);
+            assert!(chunks.iter().all(|chunk| !chunk.text.is_empty()));
+        }
+    }
+
+    #[test]
+    fn respects_the_budget_at_sentence_boundaries() {
+        let text = "One two three. Four five six. Seven eight nine.";
+        let chunks = split(text, 5);
+        let texts: Vec<&str> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
+        assert_eq!(texts, vec!["One two three.", "Four five six.", "Seven eight nine."]);
+        assert_eq!(chunks[0].separator, " ");
+        assert!(chunks.iter().all(|chunk| estimate_tokens(&chunk.text) <= 5));
+    }
+
+    #[test]
+    fn does_not_split_decimals_or_abbreviations() {
+        let text = "Pi is 3.14 roughly, e.g.the ratio.";
+        assert_eq!(sentences(text), vec![(text, "")]);
+    }
+
+    #[test]
+    fn merges_small_paragraphs() {
+        let chunks = split("a\n\nb\n\nc", 1000);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].text, "a\n\nb\n\nc");
+        assert_eq!(chunks[0].separator, "");
+    }
+
+    #[test]
+    fn hard_splits_at_whitespace() {
+        let parts = hard_split("aaaa bbbb cccc", 2);
+        assert_eq!(parts, vec![("aaaa", " "), ("bbbb", " "), ("cccc", "")]);
+
+        // 没有空白时按字符切开
+        let parts = hard_split("你好世界", 2);
+        assert_eq!(parts, vec![("你好", ""), ("世界", "")]);
+    }
+}
@@ -0,0 +1,552 @@
+use super::{estimate_tokens, Chunk};
+use crate::providers::prompt::{PLACEHOLDER_CLOSE, PLACEHOLDER_OPEN};
+use std::path::Path;
+
+/// Provider（或fallback链中的某一个）不会保留占位符时的提示
+pub const UNSUPPORTED: &str = "Markdown mode needs LLM providers (DeepL and LibreTranslate cannot keep placeholders)";
+
+/// 按扩展名判断是否为Markdown文件
+pub fn is_markdown_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "md" | "markdown" | "mdown" | "mkd"))
+}
+
+/// 解析Markdown：只有正文片段需要翻译，其余（front matter、代码、链接地址、HTML标签等）
+/// 放在各块的separator或占位符中原样保留；相邻片段合并为不超过max_tokens的块（0为不限），
+/// 首块可能只有separator
+pub fn split(text: &str, max_tokens: usize) -> Vec<Chunk> {
+    let mut parts = Parts::default();
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let mut i = front_matter(&lines);
+    parts.keep(&lines[..i].concat());
+
+    // 上一行是否为可续行的段落，以及是否处于列表中
+    let mut paragraph = false;
+    let mut in_list = false;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let content = line.trim_end_matches(['\n', '\r']);
+        let trimmed = content.trim_start();
+        let indent = content.len() - trimmed.len();
+
+        // 围栏代码块
+        if let Some((fence, count)) = fence(trimmed).filter(|_| indent <= 3) {
+            let end = lines[i + 1..]
+                .iter()
+                .position(|line| {
+                    let t = line.trim();
+                    t.len() >= count && t.chars().all(|c| c == fence)
+                })
+                .map_or(lines.len(), |offset| i + 1 + offset + 1);
+            parts.keep(&lines[i..end].concat());
+            i = end;
+            paragraph = false;
+            continue;
+        }
+
+        // HTML注释
+        if trimmed.starts_with("<!--") {
+            let end = lines[i..]
+                .iter()
+                .position(|line| line.contains("-->"))
+                .map_or(lines.len(), |offset| i + offset + 1);
+            parts.keep(&lines[i..end].concat());
+            i = end;
+            paragraph = false;
+            continue;
+        }
+
+        i += 1;
+        if trimmed.is_empty() {
+            parts.keep(line);
+            paragraph = false;
+            continue;
+        }
+
+        let indented_code = (content.starts_with("    ") || content.starts_with('\t')) && !paragraph && !in_list;
+        if indented_code || is_thematic_break(trimmed) || is_reference_definition(trimmed) || is_table_delimiter(trimmed) {
+            parts.keep(line);
+            paragraph = false;
+            continue;
+        }
+
+        let eol = &line[content.len()..];
+        if trimmed.starts_with('|') {
+            table_row(&mut parts, content, eol);
+            paragraph = false;
+            continue;
+        }
+
+        let (prefix, list_item, heading) = line_prefix(content);
+        if list_item {
+            in_list = true;
+        } else if indent == 0 && !paragraph {
+            in_list = false;
+        }
+
+        let rest = &content[prefix..];
+        let body = rest.trim_end();
+        let trailing = &rest[body.len()..];
+        // 同一段落的后续行并入上一段文本
+        if !(paragraph && prefix == indent && parts.continue_text(&content[..prefix], body)) {
+            parts.keep(&content[..prefix]);
+            parts.text(body);
+        }
+        parts.keep(trailing);
+        parts.keep(eol);
+        paragraph = !heading;
+    }
+
+    parts.into_chunks(if max_tokens == 0 { usize::MAX } else { max_tokens })
+}
+
+#[derive(Debug)]
+enum Part {
+    Keep(String),
+    Text(String),
+}
+
+#[derive(Default)]
+struct Parts(Vec<Part>);
+
+impl Parts {
+    fn keep(&mut self, s: &str) {
+        if s.is_empty() {
+            return;
+        }
+        match self.0.last_mut() {
+            Some(Part::Keep(last)) => last.push_str(s),
+            _ => self.0.push(Part::Keep(s.to_string())),
+        }
+    }
+
+    fn text(&mut self, s: &str) {
+        if !s.is_empty() {
+            self.0.push(Part::Text(s.to_string()));
+        }
+    }
+
+    /// 把上一行的换行与本行缩进并入上一段文本；上一段不是文本时返回false
+    fn continue_text(&mut self, indent: &str, body: &str) -> bool {
+        let [.., Part::Text(text), Part::Keep(glue)] = self.0.as_mut_slice() else {
+            return false;
+        };
+        text.push_str(glue);
+        text.push_str(indent);
+        text.push_str(body);
+        self.0.pop();
+        true
+    }
+
+    fn into_chunks(self, max_tokens: usize) -> Vec<Chunk> {
+        let mut segments = Vec::new();
+        let mut current = Chunk::default();
+        for part in self.0 {
+            match part {
+                Part::Keep(s) => current.separator.push_str(&s),
+                Part::Text(s) => {
+                    let mut chunk = Chunk::default();
+                    protect_inline(&mut chunk, &s);
+                    if has_words(&chunk.text) {
+                        segments.push(std::mem::replace(&mut current, chunk));
+                    } else {
+                        current.separator.push_str(&s);
+                    }
+                }
+            }
+        }
+        segments.push(current);
+
+        // 相邻片段按预算合并，减少请求次数并让模型看到上下文
+        let mut chunks: Vec<Chunk> = Vec::new();
+        for segment in segments {
+            match chunks.last_mut() {
+                Some(chunk)
+                    if !chunk.text.is_empty()
+                        && estimate_tokens(&chunk.text) + estimate_tokens(&segment.text) <= max_tokens =>
+                {
+                    chunk.append(segment)
+                }
+                _ => chunks.push(segment),
+            }
+        }
+        chunks
+    }
+}
+
+/// front matter（--- 或 +++ 包围）的行数
+fn front_matter(lines: &[&str]) -> usize {
+    let Some(first) = lines.first().map(|line| line.trim_end()) else {
+        return 0;
+    };
+    if first != "---" && first != "+++" {
+        return 0;
+    }
+    lines[1..]
+        .iter()
+        .position(|line| {
+            let line = line.trim_end();
+            line == first || (first == "---" && line == "...")
+        })
+        .map_or(0, |offset| offset + 2)
+}
+
+/// 围栏起始行：返回围栏字符与长度
+fn fence(trimmed: &str) -> Option<(char, usize)> {
+    let c = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let count = trimmed.chars().take_while(|&ch| ch == c).count();
+    (count >= 3).then_some((c, count))
+}
+
+fn is_thematic_break(trimmed: &str) -> bool {
+    let Some(c) = trimmed.chars().next().filter(|c| matches!(c, '-' | '*' | '_' | '=')) else {
+        return false;
+    };
+    // === 为Setext标题下划线
+    let count = trimmed.chars().filter(|&ch| ch == c).count();
+    trimmed.chars().all(|ch| ch == c || ch == ' ') && (count >= 3 || c == '=')
+}
+
+/// [id]: url "title"（脚注定义 [^id]: 的正文需要翻译，不在此列）
+fn is_reference_definition(trimmed: &str) -> bool {
+    trimmed.starts_with('[') && !trimmed.starts_with("[^") && trimmed.find("]:").is_some_and(|end| !trimmed[..end].contains("]("))
+}
+
+fn is_table_delimiter(trimmed: &str) -> bool {
+    trimmed.contains('-') && trimmed.contains('|') && trimmed.chars().all(|c| matches!(c, '|' | ':' | '-' | ' '))
+}
+
+/// 表格行：逐个单元格翻译，竖线与空白保留
+fn table_row(parts: &mut Parts, content: &str, eol: &str) {
+    let mut start = 0;
+    let mut in_code = false;
+    let mut escaped = false;
+    for (i, c) in content.char_indices() {
+        match c {
+            '\\' if !escaped => {
+                escaped = true;
+                continue;
+            }
+            '`' if !escaped => in_code = !in_code,
+            '|' if !escaped && !in_code => {
+                cell(parts, &content[start..i]);
+                parts.keep("|");
+                start = i + 1;
+            }
+            _ => {}
+        }
+        escaped = false;
+    }
+    cell(parts, &content[start..]);
+    parts.keep(eol);
+}
+
+fn cell(parts: &mut Parts, cell: &str) {
+    let body = cell.trim();
+    let leading = cell.len() - cell.trim_start().len();
+    parts.keep(&cell[..leading]);
+    parts.text(body);
+    parts.keep(&cell[leading + body.len()..]);
+}
+
+/// 行首不翻译的部分：缩进、引用符号、列表标记、任务框、标题井号、脚注标签
+/// 返回 (前缀长度, 是否列表项, 是否标题)
+fn line_prefix(content: &str) -> (usize, bool, bool) {
+    let mut pos = content.len() - content.trim_start().len();
+    let mut list_item = false;
+
+    // 引用（可嵌套）
+    while content[pos..].starts_with('>') {
+        pos += 1;
+        pos += content[pos..].len() - content[pos..].trim_start().len();
+    }
+
+    let rest = &content[pos..];
+    if let Some(marker) = list_marker(rest) {
+        pos += marker;
+        list_item = true;
+        let rest = &content[pos..];
+        for task in ["[ ] ", "[x] ", "[X] "] {
+            if rest.starts_with(task) {
+                pos += task.len();
+            }
+        }
+    }
+
+    let rest = &content[pos..];
+    if rest.starts_with("[^") {
+        if let Some(end) = rest.find("]: ") {
+            return (pos + end + 3, false, false);
+        }
+    }
+
+    let hashes = rest.chars().take_while(|&c| c == '#').count();
+    let heading = (1..=6).contains(&hashes) && rest[hashes..].starts_with(' ');
+    if heading {
+        pos += hashes + 1;
+    }
+    (pos, list_item, heading)
+}
+
+/// 列表标记（含其后的空格）的长度
+fn list_marker(rest: &str) -> Option<usize> {
+    let digits = rest.chars().take_while(char::is_ascii_digit).count();
+    let marker = if rest.starts_with(['-', '*', '+']) {
+        1
+    } else if (1..=9).contains(&digits) && rest[digits..].starts_with(['.', ')']) {
+        digits + 1
+    } else {
+        return None;
+    };
+    let spaces = rest[marker..].chars().take_while(|&c| c == ' ').count();
+    (spaces > 0).then_some(marker + spaces)
+}
+
+/// 行内：代码、链接地址、自动链接、HTML标签、脚注与裸URL用占位符保护
+fn protect_inline(chunk: &mut Chunk, text: &str) {
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap();
+
+        if c == '\\' {
+            // 转义字符原样保留
+            let escaped = rest.chars().nth(1).map_or(0, char::len_utf8);
+            chunk.text.push_str(&rest[..1 + escaped]);
+            i += 1 + escaped;
+            continue;
+        }
+
+        if c == '`' {
+            let ticks = rest.chars().take_while(|&ch| ch == '`').count();
+            let fence = &rest[..ticks];
+            if let Some(close) = find_backticks(&rest[ticks..], ticks) {
+                let end = ticks + close + ticks;
+                chunk.protect(&rest[..end]);
+                i += end;
+            } else {
+                chunk.text.push_str(fence);
+                i += ticks;
+            }
+            continue;
+        }
+
+        if c == '[' || rest.starts_with("![") {
+            if let Some(consumed) = link(chunk, rest) {
+                i += consumed;
+                continue;
+            }
+        }
+
+        if c == '<' {
+            if let Some(end) = rest.find('>').filter(|&end| is_tag(&rest[1..end])) {
+                chunk.protect(&rest[..=end]);
+                i += end + 1;
+                continue;
+            }
+        }
+
+        if rest.starts_with("http://") || rest.starts_with("https://") {
+            let end = rest.find(|ch: char| ch.is_whitespace() || matches!(ch, '<' | '>' | '"')).unwrap_or(rest.len());
+            let end = rest[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')']).len();
+            chunk.protect(&rest[..end]);
+            i += end;
+            continue;
+        }
+
+        // 原文中恰好出现的占位符号也保护起来，避免与占位符混淆
+        if c == PLACEHOLDER_OPEN || c == PLACEHOLDER_CLOSE {
+            chunk.protect(&rest[..c.len_utf8()]);
+        } else {
+            chunk.text.push(c);
+        }
+        i += c.len_utf8();
+    }
+}
+
+/// [text](url)、![alt](url)、[text][ref]、[^note]：链接文字照常翻译，其余用占位符保护
+fn link(chunk: &mut Chunk, rest: &str) -> Option<usize> {
+    let open = if rest.starts_with("![") { 2 } else { 1 };
+    if rest[open..].starts_with('^') {
+        let end = rest.find(']')?;
+        chunk.protect(&rest[..=end]);
+        return Some(end + 1);
+    }
+
+    let close = open + matching(&rest[open..], '[', ']')?;
+    let after = &rest[close + 1..];
+    let target = if let Some(inner) = after.strip_prefix('(') {
+        matching(inner, '(', ')')? + 2
+    } else if after.starts_with('[') {
+        after.find(']')? + 1
+    } else {
+        return None;
+    };
+
+    chunk.protect(&rest[..open]);
+    protect_inline(chunk, &rest[open..close]);
+    chunk.protect(&rest[close..close + 1 + target]);
+    Some(close + 1 + target)
+}
+
+/// 找到与开括号匹配的闭括号位置（跳过嵌套与转义）
+fn matching(text: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == open {
+            depth += 1;
+        } else if c == close {
+            if depth == 0 {
+                return Some(i);
+            }
+            depth -= 1;
+        }
+    }
+    None
+}
+
+/// 与开头同样长度的反引号串的位置
+fn find_backticks(text: &str, count: usize) -> Option<usize> {
+    let mut i = 0;
+    while let Some(offset) = text[i..].find('`') {
+        let start = i + offset;
+        let run = text[start..].chars().take_while(|&c| c == '`').count();
+        if run == count {
+            return Some(start);
+        }
+        i = start + run;
+    }
+    None
+}
+
+/// <...> 内是HTML标签、注释或自动链接
+fn is_tag(inner: &str) -> bool {
+    let Some(first) = inner.chars().next() else {
+        return false;
+    };
+    if first.is_ascii_alphabetic() && (inner.contains("://") || inner.contains('@')) {
+        return !inner.contains(char::is_whitespace);
+    }
+    first.is_ascii_alphabetic() || first == '/' || first == '!'
+}
+
+/// 去掉占位符后是否还有需要翻译的文字
+fn has_words(text: &str) -> bool {
+    let mut in_placeholder = false;
+    text.chars().any(|c| {
+        if c == PLACEHOLDER_OPEN {
+            in_placeholder = true;
+        } else if c == PLACEHOLDER_CLOSE {
+            in_placeholder = false;
+        } else if !in_placeholder && c.is_alphabetic() {
+            return true;
+        }
+        false
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = "---\ntitle: Demo\n---\n# Getting started\n\nInstall it with `cargo install`, see [the docs](https://example.com/docs \"Docs\").\nIt works\nacross lines.\n\n```rust\nfn main() {}\n```\n\n- [ ] First item\n- Second <b>bold</b> item\n\n| Name | Value |\n|------|-------|\n| Size | Large |\n\n> Quoted text[^1]\n\n[^1]: A footnote.\n";
+
+    /// 原文拼回（不翻译）
+    fn join(chunks: &[Chunk]) -> String {
+        chunks.iter().map(|chunk| chunk.original() + &chunk.separator).collect()
+    }
+
+    #[test]
+    fn split_round_trips() {
+        for max_tokens in [0, 1, 8, 40] {
+            assert_eq!(join(&split(DOCUMENT, max_tokens)), DOCUMENT, "max_tokens = {}", max_tokens);
+        }
+    }
+
+    #[test]
+    fn only_prose_is_translated() {
+        let chunks = split(DOCUMENT, 1);
+        let texts: Vec<&str> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "",
+                "Getting started",
+                "Install it with ⟦0⟧, see ⟦1⟧the docs⟦2⟧.\nIt works\nacross lines.",
+                "First item",
+                "Second ⟦0⟧bold⟦1⟧ item",
+                "Name",
+                "Value",
+                "Size",
+                "Large",
+                "Quoted text⟦0⟧",
+                "A footnote.",
+            ]
+        );
+        assert_eq!(chunks[0].separator, "---\ntitle: Demo\n---\n# ");
+        assert_eq!(chunks[2].protected, vec!["`cargo install`", "[", "](https://example.com/docs \"Docs\")"]);
+        assert!(chunks[2].separator.contains("```rust\nfn main() {}\n```"));
+    }
+
+    #[test]
+    fn batches_segments_within_the_budget() {
+        let chunks = split(DOCUMENT, 40);
+        assert!(chunks.len() < split(DOCUMENT, 1).len());
+        let batched = chunks.iter().find(|chunk| !chunk.segments.is_empty()).unwrap();
+        assert!(!batched.breaks.is_empty());
+        assert!(chunks.iter().all(|chunk| estimate_tokens(&chunk.text) <= 40 || chunk.segments.is_empty()));
+
+        // 不限预算时正文合并为一块
+        let chunks = split(DOCUMENT, 0);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1].segments.len(), 10);
+    }
+
+    #[test]
+    fn restores_batched_translation() {
+        let chunks = split("# Title\n\nSome `code` here.\n", 0);
+        assert_eq!(chunks[1].text, "Title⟦0⟧Some ⟦1⟧ here.");
+        // 模型在段落占位符两侧加的空白被去掉
+        let restored = chunks[1].restore("Titel\n⟦0⟧\nEtwas ⟦1⟧ hier.").unwrap();
+        assert_eq!(format!("{}{}{}", chunks[0].separator, restored, chunks[1].separator), "# Titel\n\nEtwas `code` hier.\n");
+    }
+
+    #[test]
+    fn restore_rejects_broken_placeholders() {
+        let chunk = &split("# Title\n\nSome `code` here.\n", 0)[1];
+        // 缺失、重复、编号越界、未闭合、段落顺序错乱
+        for translation in [
+            "Titel Etwas ⟦1⟧ hier.",
+            "Titel⟦0⟧Etwas ⟦1⟧⟦1⟧ hier.",
+            "Titel⟦0⟧Etwas ⟦1⟧ hier ⟦2⟧.",
+            "Titel⟦0⟧Etwas ⟦1⟧ hier ⟦3",
+        ] {
+            assert_eq!(chunk.restore(translation), None, "{}", translation);
+        }
+
+        let chunk = &split("One\n\nTwo\n\nThree\n", 0)[1];
+        assert_eq!(chunk.restore("Eins⟦1⟧Drei⟦0⟧Zwei"), None);
+        assert_eq!(chunk.restore("Eins⟦0⟧Zwei⟦1⟧Drei").as_deref(), Some("Eins\n\nZwei\n\nDrei"));
+    }
+
+    #[test]
+    fn literal_placeholder_marks_are_protected() {
+        let chunks = split("Use ⟦0⟧ as a marker\n", 0);
+        assert_eq!(chunks[1].text, "Use ⟦0⟧0⟦1⟧ as a marker");
+        assert_eq!(join(&chunks), "Use ⟦0⟧ as a marker\n");
+    }
+
+    #[test]
+    fn detects_markdown_paths() {
+        assert!(is_markdown_path(Path::new("README.md")));
+        assert!(is_markdown_path(Path::new("docs/guide.Markdown")));
+        assert!(!is_markdown_path(Path::new("notes.txt")));
+    }
+}
@@ -0,0 +1,190 @@
+pub mod chunk;
+pub mod markdown;
+
+pub use chunk::{estimate_tokens, Chunk};
+
+use crate::config::settings::DocumentConfig;
+use crate::providers::{LanguagePair, NamedStream, Responder, TranslationProvider};
+use anyhow::Result;
+use futures::{future, stream, StreamExt};
+use std::sync::Arc;
+
+/// 作为上下文附带的前文长度（字符）
+const CONTEXT_CHARS: usize = 600;
+
+/// 超过每块预算时需要分块翻译
+pub fn needs_chunking(text: &str, config: &DocumentConfig) -> bool {
+    config.chunk_tokens > 0 && estimate_tokens(text) > config.chunk_tokens
+}
+
+pub fn split(text: &str, config: &DocumentConfig) -> Vec<Chunk> {
+    chunk::split(text, config.chunk_tokens)
+}
+
+/// 逐块翻译，按原顺序输出每块译文及其后的分隔空白，并标注应答的Provider（无需请求的块为None）；出错后停止
+pub fn translate(
+    provider: Arc<dyn TranslationProvider>,
+    chunks: Vec<Chunk>,
+    languages: &LanguagePair,
+    config: &DocumentConfig,
+) -> NamedStream {
+    let languages = languages.clone();
+    let use_context = config.context;
+
+    let results: NamedStream = if config.concurrency <= 1 {
+        // 顺序翻译：上一块的译文作为上下文
+        Box::pin(stream::unfold(
+            (chunks.into_iter(), String::new()),
+            move |(mut chunks, previous)| {
+                let provider = Arc::clone(&provider);
+                let languages = languages.clone();
+                async move {
+                    let chunk = chunks.next()?;
+                    if chunk.text.trim().is_empty() {
+                        return Some((Ok((None, chunk.separator)), (chunks, previous)));
+                    }
+                    let context = if use_context { tail(&previous) } else { "" };
+                    let result = translate_chunk(provider.as_ref(), &chunk, &languages, context).await;
+                    let translation = result.as_ref().map(|(_, t)| t.clone()).unwrap_or_default();
+                    let output = result.map(|(responder, _)| (Some(responder), format!("{}{}", translation, chunk.separator)));
+                    Some((output, (chunks, translation)))
+                }
+            },
+        ))
+    } else {
+        // 并发翻译：只能以上一块的原文作为上下文
+        let contexts: Vec<String> = std::iter::once(String::new())
+            .chain(chunks.iter().map(|chunk| tail(&chunk.text).to_string()))
+            .collect();
+        Box::pin(
+            stream::iter(chunks.into_iter().zip(contexts))
+                .map(move |(chunk, context)| {
+                    let provider = Arc::clone(&provider);
+                    let languages = languages.clone();
+                    async move {
+                        if chunk.text.trim().is_empty() {
+                            return Ok((None, chunk.separator));
+                        }
+                        let context = if use_context { context.as_str() } else { "" };
+                        let (responder, translation) = translate_chunk(provider.as_ref(), &chunk, &languages, context).await?;
+                        Ok((Some(responder), format!("{}{}", translation, chunk.separator)))
+                    }
+                })
+                .buffered(config.concurrency),
+        )
+    };
+
+    Box::pin(results.scan(false, |failed, result| {
+        if *failed {
+            return future::ready(None);
+        }
+        *failed = result.is_err();
+        future::ready(Some(result))
+    }))
+}
+
+/// 翻译一块并换回占位符；模型没有原样保留占位符时逐段重译，仍失败的段落保留原文
+async fn translate_chunk(
+    provider: &dyn TranslationProvider,
+    chunk: &Chunk,
+    languages: &LanguagePair,
+    context: &str,
+) -> Result<(Responder, String)> {
+    let (mut responder, translation) = provider.translate_named(&chunk.text, languages, context).await?;
+    if let Some(translation) = chunk.restore(&translation) {
+        return Ok((responder, translation));
+    }
+    if chunk.segments.is_empty() {
+        return Ok((responder, chunk.original()));
+    }
+
+    let mut output = String::new();
+    let last = chunk.segments.len() - 1;
+    for (index, segment) in chunk.segments.iter().enumerate() {
+        let (answered, translation) = provider.translate_named(&segment.text, languages, context).await?;
+        responder = answered;
+        output.push_str(&segment.restore(&translation).unwrap_or_else(|| segment.original()));
+        if index < last {
+            output.push_str(&segment.separator);
+        }
+    }
+    Ok((responder, output))
+}
+
+/// 文本末尾最多CONTEXT_CHARS个字符
+fn tail(text: &str) -> &str {
+    let start = text
+        .char_indices()
+        .rev()
+        .nth(CONTEXT_CHARS - 1)
+        .map_or(0, |(i, _)| i);
+    &text[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{fallback::FallbackProvider, fake::FakeProvider};
+
+    fn chunk(text: &str, separator: &str) -> Chunk {
+        Chunk {
+            text: text.to_string(),
+            separator: separator.to_string(),
+            ..Chunk::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_the_provider_of_each_chunk() {
+        let primary: Arc<dyn TranslationProvider> =
+            Arc::new(FakeProvider::new("primary", vec![Ok("one"), Err(429), Err(429)]));
+        let backup: Arc<dyn TranslationProvider> = Arc::new(FakeProvider::new("backup", vec![Ok("two")]));
+        let provider = Arc::new(FallbackProvider::new(vec![primary, backup], false));
+
+        let chunks = vec![chunk("first", "\n\n"), chunk("", "\n"), chunk("second", "")];
+        let config = DocumentConfig::default();
+        let output: Vec<_> = translate(provider, chunks, &LanguagePair::default(), &config)
+            .map(|result| result.unwrap())
+            .map(|(responder, text)| (responder.map(|r| r.name), text))
+            .collect()
+            .await;
+
+        assert_eq!(
+            output,
+            vec![
+                (Some("primary".to_string()), "one\n\n".to_string()),
+                (None, "\n".to_string()),
+                (Some("backup".to_string()), "two".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn retranslates_segments_when_placeholders_are_lost() {
+        // 整块译文丢了占位符，逐段重译（FakeProvider原样返回）后保持结构
+        let text = "# Title\n\nSome `code` here.\n";
+        let provider = Arc::new(FakeProvider::new("fake", vec![Ok("Titel Etwas hier.")]));
+        let chunks = markdown::split(text, 0);
+        let config = DocumentConfig::default();
+        let output: String = translate(provider.clone(), chunks, &LanguagePair::default(), &config)
+            .map(|result| result.unwrap().1)
+            .collect()
+            .await;
+
+        assert_eq!(output, text);
+        assert_eq!(provider.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_original_segment_when_placeholders_are_lost() {
+        let text = "Run `make` first.\n";
+        let provider = Arc::new(FakeProvider::new("fake", vec![Ok("Zuerst ausführen.")]));
+        let config = DocumentConfig::default();
+        let output: String = translate(provider, markdown::split(text, 0), &LanguagePair::default(), &config)
+            .map(|result| result.unwrap().1)
+            .collect()
+            .await;
+
+        assert_eq!(output, text);
+    }
+}
@@ -1,65 +1,132 @@
-use crate::app::{App, AppMessage};
-use crate::providers::TranslationProvider;
+use crate::app::App;
+use crate::history::ExportFormat;
+use crate::utils::clipboard;
 use anyhow::Result;
-use arboard::Clipboard;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use futures::StreamExt;
-use std::sync::Arc;
-
-pub async fn handle_key_event<P: TranslationProvider + 'static>(
-    key: KeyEvent,
-    app: &mut App,
-    provider: Arc<P>,
-) -> Result<()> {
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+
+pub async fn handle_key_event(key: KeyEvent, app: &mut App) -> Result<()> {
+    // Profile popup captures all keys while open
+    if app.profile_popup.is_some() {
+        handle_profile_popup_key(key, app);
+        return Ok(());
+    }
+    if app.model_popup.is_some() {
+        handle_model_popup_key(key, app);
+        return Ok(());
+    }
+    if app.history_search.is_some() {
+        handle_history_search_key(key, app);
+        return Ok(());
+    }
+    if app.search.is_some() {
+        handle_search_key(key, app);
+        return Ok(());
+    }
+    if app.export_popup.is_some() {
+        handle_export_popup_key(key, app);
+        return Ok(());
+    }
+
     match key.code {
-        // ESC: Quit
+        // ESC: Cancel streaming translation, otherwise quit
+        KeyCode::Esc if app.is_streaming() => {
+            app.cancel_latest();
+        }
+        KeyCode::Esc if app.selected.is_some() || app.highlight.is_some() => {
+            app.clear_selection();
+        }
+        KeyCode::Esc if app.editing.is_some() => {
+            app.cancel_edit();
+        }
         KeyCode::Esc => {
             app.should_quit = true;
         }
 
+        // Ctrl+X: Cancel streaming translation
+        KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.cancel_latest();
+        }
+
+        // Shift+Enter / Alt+Enter: New line
+        KeyCode::Enter if key.modifiers.intersects(KeyModifiers::SHIFT | KeyModifiers::ALT) => {
+            app.input.insert_char('\n');
+        }
+
         // Enter: Submit translation
-        KeyCode::Enter => {
-            if !app.input.is_empty() {
-                let input_text = app.input.clone();
-                app.input.clear();
-
-                // Create message
-                let mut message = app.create_message(input_text.clone());
-                message.start_streaming();
-                let msg_id = message.id;
-                app.add_message(message);
-
-                // Spawn translation task
-                let tx = app.tx.clone();
-                let provider_clone = Arc::clone(&provider);
-
-                tokio::spawn(async move {
-                    match provider_clone.translate_stream(&input_text).await {
-                        Ok(mut stream) => {
-                            while let Some(result) = stream.next().await {
-                                match result {
-                                    Ok(delta) => {
-                                        if !delta.is_empty() {
-                                            let _ = tx.send(AppMessage::TranslationDelta(msg_id, delta));
-                                        }
-                                    }
-                                    Err(e) => {
-                                        let _ = tx.send(AppMessage::TranslationError(
-                                            msg_id,
-                                            e.to_string(),
-                                        ));
-                                        return;
-                                    }
-                                }
-                            }
-                            let _ = tx.send(AppMessage::TranslationComplete(msg_id));
-                        }
-                        Err(e) => {
-                            let _ = tx.send(AppMessage::TranslationError(msg_id, e.to_string()));
-                        }
-                    }
-                });
-            }
+        KeyCode::Enter if !app.input.text().trim().is_empty() => {
+            app.submit_input();
+        }
+
+        // /: Search messages and history (while the input is empty)
+        KeyCode::Char('/') if app.input.is_empty() => {
+            app.open_search();
+        }
+
+        // Ctrl+S: Export the session to a file
+        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.open_export_popup();
+        }
+
+        // Ctrl+R: Reverse search input history
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.search_history();
+        }
+
+        // Alt+Up/Down: Select a message in the history
+        KeyCode::Up if key.modifiers.contains(KeyModifiers::ALT) => {
+            app.move_selection(-1);
+        }
+        KeyCode::Down if key.modifiers.contains(KeyModifiers::ALT) => {
+            app.move_selection(1);
+        }
+
+        // Scroll the chat history by line / page, jump to top / bottom
+        KeyCode::Up if key.modifiers.contains(KeyModifiers::CONTROL) => app.scroll_by(-1),
+        KeyCode::Down if key.modifiers.contains(KeyModifiers::CONTROL) => app.scroll_by(1),
+        KeyCode::PageUp => app.scroll_page(-1),
+        KeyCode::PageDown => app.scroll_page(1),
+        // ↑/↓ and Home/End belong to the input box; Home/End scroll only while it is empty
+        KeyCode::Home if key.modifiers.contains(KeyModifiers::CONTROL) || app.input.is_empty() => {
+            app.scroll_to_top()
+        }
+        KeyCode::End if key.modifiers.contains(KeyModifiers::CONTROL) || app.input.is_empty() => {
+            app.scroll_to_bottom()
+        }
+
+        // Alt+M: Toggle mouse capture (wheel scrolling vs. native text selection)
+        KeyCode::Char('m') if key.modifiers.contains(KeyModifiers::ALT) => {
+            app.toggle_mouse_capture();
+        }
+
+        // Ctrl+G: Regenerate selected (or latest) message
+        KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.regenerate(None);
+        }
+
+        // Alt+G: Regenerate with another model
+        KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::ALT) => {
+            app.open_model_popup();
+        }
+
+        // Ctrl+E: Edit selected (or latest) message and resubmit
+        KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.edit_message();
+        }
+
+        // Ctrl+P: Switch profile
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.open_profile_popup();
+        }
+
+        // Ctrl+T: Toggle compare mode
+        KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.toggle_compare_mode();
+        }
+
+        // Alt+1-9: Pick compare result for copying
+        KeyCode::Char(c @ '1'..='9') if key.modifiers.contains(KeyModifiers::ALT) => {
+            let index = c.to_digit(10).unwrap() as usize - 1;
+            app.select_candidate(index);
         }
 
         // TAB: Toggle display mode
@@ -67,9 +134,20 @@ pub async fn handle_key_event<P: TranslationProvider + 'static>(
             app.toggle_display_mode();
         }
 
-        // Backspace: Delete character
-        KeyCode::Backspace => {
-            app.input.pop();
+        // Input editing
+        KeyCode::Backspace => app.input.backspace(),
+        KeyCode::Delete => app.input.delete(),
+        KeyCode::Left => app.input.move_left(),
+        KeyCode::Right => app.input.move_right(),
+        KeyCode::Home => app.input.move_home(),
+        KeyCode::End => app.input.move_end(),
+        KeyCode::Up => app.input_up(),
+        KeyCode::Down => app.input_down(),
+        KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.input.delete_word();
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.input.delete_to_line_start();
         }
 
         // Ctrl+C: Clear history
@@ -78,12 +156,10 @@ pub async fn handle_key_event<P: TranslationProvider + 'static>(
         }
 
         // Ctrl+Y: Copy latest translation
-        KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+        KeyCode::Char('y') if key.modifiers == KeyModifiers::CONTROL => {
             if let Some(translation) = app.get_latest_translation() {
-                if let Ok(mut clipboard) = Clipboard::new() {
-                    if clipboard.set_text(&translation).is_ok() {
-                        app.show_notification("Copied latest translation to clipboard");
-                    }
+                if clipboard::copy_text(&translation) {
+                    app.show_notification("Copied latest translation to clipboard");
                 }
             } else {
                 app.show_notification("No translation available to copy");
@@ -91,41 +167,37 @@ pub async fn handle_key_event<P: TranslationProvider + 'static>(
         }
 
         // Ctrl+Shift+Y: Copy all translations
-        KeyCode::Char('Y') if key.modifiers.contains(KeyModifiers::CONTROL | KeyModifiers::SHIFT) =>
+        KeyCode::Char('Y' | 'y') if key.modifiers.contains(KeyModifiers::CONTROL | KeyModifiers::SHIFT) =>
         {
             let translations = app.get_all_translations();
             if !translations.is_empty() {
                 let combined = translations.join("\n\n");
-                if let Ok(mut clipboard) = Clipboard::new() {
-                    if clipboard.set_text(&combined).is_ok() {
-                        app.show_notification(format!(
-                            "Copied {} translations to clipboard",
-                            translations.len()
-                        ));
-                    }
+                if clipboard::copy_text(&combined) {
+                    app.show_notification(format!(
+                        "Copied {} translations to clipboard",
+                        translations.len()
+                    ));
                 }
             } else {
                 app.show_notification("No translations available to copy");
             }
         }
 
-        // Number keys 1-9: Copy specific translation
-        KeyCode::Char(c @ '1'..='9') => {
+        // Number keys 1-9: Copy specific translation (while the input is empty)
+        KeyCode::Char(c @ '1'..='9') if app.input.is_empty() => {
             let index = c.to_digit(10).unwrap() as usize - 1;
             if let Some(translation) = app.get_translation_by_index(index) {
-                if let Ok(mut clipboard) = Clipboard::new() {
-                    if clipboard.set_text(&translation).is_ok() {
-                        app.show_notification(format!("Copied translation #{} to clipboard", index + 1));
-                    }
+                if clipboard::copy_text(&translation) {
+                    app.show_notification(format!("Copied translation #{} to clipboard", index + 1));
                 }
             } else {
                 app.show_notification(format!("Translation #{} not found", index + 1));
             }
         }
 
-        // Regular character input
-        KeyCode::Char(c) => {
-            app.input.push(c);
+        // Regular character input (unbound Ctrl combinations are ignored)
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.input.insert_char(c);
         }
 
         _ => {}
@@ -133,3 +205,126 @@ pub async fn handle_key_event<P: TranslationProvider + 'static>(
 
     Ok(())
 }
+
+/// 鼠标滚轮滚动聊天记录
+pub fn handle_mouse_event(mouse: MouseEvent, app: &mut App) {
+    match mouse.kind {
+        MouseEventKind::ScrollUp => app.scroll_by(-3),
+        MouseEventKind::ScrollDown => app.scroll_by(3),
+        _ => {}
+    }
+}
+
+/// 粘贴（bracketed paste）：整段插入输入框，换行不会触发提交
+pub fn handle_paste(text: String, app: &mut App) {
+    if app.history_search.is_some() {
+        app.update_history_search(|query| query.push_str(&text));
+    } else if app.search.is_some() {
+        app.update_search(|query| query.push_str(&text));
+    } else if app.profile_popup.is_none() && app.model_popup.is_none() && app.export_popup.is_none() {
+        app.input.insert_str(&text);
+    }
+}
+
+fn handle_search_key(key: KeyEvent, app: &mut App) {
+    match key.code {
+        KeyCode::Esc => app.search = None,
+        KeyCode::Up => {
+            if let Some(search) = &mut app.search {
+                search.move_selection(-1);
+            }
+        }
+        KeyCode::Down | KeyCode::Tab => {
+            if let Some(search) = &mut app.search {
+                search.move_selection(1);
+            }
+        }
+        KeyCode::Enter => app.jump_to_search_hit(),
+        KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.copy_search_hit();
+        }
+        KeyCode::Backspace => app.update_search(|query| {
+            query.pop();
+        }),
+        KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            app.update_search(|query| query.push(c));
+        }
+        _ => {}
+    }
+}
+
+fn handle_history_search_key(key: KeyEvent, app: &mut App) {
+    match key.code {
+        KeyCode::Esc => app.finish_history_search(false),
+        KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.finish_history_search(false);
+        }
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.search_history();
+        }
+        KeyCode::Backspace => app.update_history_search(|query| {
+            query.pop();
+        }),
+        KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            app.update_history_search(|query| query.push(c));
+        }
+        // Enter或其他按键：采用匹配项继续编辑
+        _ => app.finish_history_search(true),
+    }
+}
+
+fn handle_export_popup_key(key: KeyEvent, app: &mut App) {
+    match key.code {
+        KeyCode::Esc => {
+            app.export_popup = None;
+        }
+        KeyCode::Up => app.move_export_selection(-1),
+        KeyCode::Down | KeyCode::Tab => app.move_export_selection(1),
+        KeyCode::Enter => {
+            if let Some(index) = app.export_popup.take() {
+                app.export_messages(ExportFormat::ALL[index]);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_model_popup_key(key: KeyEvent, app: &mut App) {
+    match key.code {
+        KeyCode::Esc => {
+            app.model_popup = None;
+        }
+        KeyCode::Up => app.move_model_selection(-1),
+        KeyCode::Down | KeyCode::Tab => app.move_model_selection(1),
+        KeyCode::Enter => {
+            if let Some(index) = app.model_popup.take() {
+                if let Some(entry) = app.model_choices().get(index).cloned() {
+                    if let Err(e) = app.regenerate_with(&entry) {
+                        app.show_notification(format!("Failed to regenerate: {}", e));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_profile_popup_key(key: KeyEvent, app: &mut App) {
+    match key.code {
+        KeyCode::Esc => {
+            app.profile_popup = None;
+        }
+        KeyCode::Up => app.move_profile_selection(-1),
+        KeyCode::Down | KeyCode::Tab => app.move_profile_selection(1),
+        KeyCode::Enter => {
+            if let Some(index) = app.profile_popup.take() {
+                if let Some(name) = app.profile_names().get(index).cloned() {
+                    if let Err(e) = app.switch_profile(&name) {
+                        app.show_notification(format!("Failed to switch profile: {}", e));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -1,3 +1,3 @@
 pub mod handler;
 
-pub use handler::handle_key_event;
+pub use handler::{handle_key_event, handle_mouse_event, handle_paste};
@@ -0,0 +1,200 @@
+use super::{export, ExportFormat, HistoryEntry, HistoryStore};
+use anyhow::{anyhow, Result};
+use chrono::{Local, Utc};
+use std::path::PathBuf;
+use unicode_width::UnicodeWidthChar;
+
+/// `history` 子命令
+#[derive(clap::Subcommand, Debug)]
+pub enum HistoryCommand {
+    /// List recent translations
+    List {
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Search originals and translations (case-insensitive)
+    Search {
+        query: String,
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Show one entry in full
+    Show { id: u64 },
+    /// Export entries as Markdown, JSON lines, CSV or HTML
+    Export {
+        /// Output file [default: stdout]
+        #[arg(short = 'o', long)]
+        output: Option<PathBuf>,
+        /// Output format [default: from the file extension, otherwise json]
+        #[arg(short = 'f', long, value_enum)]
+        format: Option<ExportFormat>,
+        /// Only export the most recent session
+        #[arg(long = "last-session")]
+        last_session: bool,
+    },
+    /// Delete entries older than the given age, e.g. 30d, 12h, 2w
+    Prune {
+        #[arg(long = "older-than", value_name = "AGE", value_parser = parse_age)]
+        older_than: chrono::Duration,
+    },
+}
+
+pub fn run(command: &HistoryCommand) -> Result<()> {
+    let store = HistoryStore::open_default()?;
+
+    match command {
+        HistoryCommand::List { limit } => {
+            let entries = store.load()?;
+            print_list(entries.iter().rev().take(*limit));
+        }
+        HistoryCommand::Search { query, limit } => {
+            let query = query.to_lowercase();
+            let entries = store.load()?;
+            let matches: Vec<_> = entries
+                .iter()
+                .rev()
+                .filter(|entry| {
+                    entry.original.to_lowercase().contains(&query)
+                        || entry.translation.to_lowercase().contains(&query)
+                })
+                .take(*limit)
+                .collect();
+            if matches.is_empty() {
+                eprintln!("No matches");
+            }
+            print_list(matches.into_iter());
+        }
+        HistoryCommand::Show { id } => {
+            let entries = store.load()?;
+            let entry = entries
+                .iter()
+                .find(|entry| entry.id == *id)
+                .ok_or_else(|| anyhow!("No history entry with id {}", id))?;
+            print_entry(entry);
+        }
+        HistoryCommand::Export { output, format, last_session } => {
+            let entries = if *last_session { store.last_session()? } else { store.load()? };
+            let format = format
+                .or_else(|| output.as_deref().and_then(ExportFormat::from_path))
+                .unwrap_or(ExportFormat::Json);
+            match output {
+                Some(path) => {
+                    export::write(path, format, &entries)?;
+                    eprintln!("Exported {} entries to {}", entries.len(), path.display());
+                }
+                None => print!("{}", format.render(&entries)?),
+            }
+        }
+        HistoryCommand::Prune { older_than } => {
+            let removed = store.prune(Utc::now() - *older_than)?;
+            println!("Removed {} entries from {}", removed, store.path().display());
+        }
+    }
+    Ok(())
+}
+
+/// 每条一行：id、时间、语言、Provider、状态、原文 → 译文
+fn print_list<'a>(entries: impl Iterator<Item = &'a HistoryEntry>) {
+    for entry in entries {
+        println!(
+            "{:>5}  {}  {} → {}  {}  {} {} → {}",
+            entry.id,
+            entry.timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+            entry.source,
+            entry.target,
+            entry.provider,
+            entry.status.symbol(),
+            truncate(&entry.original, 30),
+            truncate(&entry.translation, 30)
+        );
+    }
+}
+
+fn print_entry(entry: &HistoryEntry) {
+    println!("Id:          {}", entry.id);
+    println!("Session:     {}", entry.session);
+    println!(
+        "Time:        {} ({:.2}s)",
+        entry.timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+        (entry.finished - entry.timestamp).num_milliseconds() as f64 / 1000.0
+    );
+    println!("Languages:   {} → {}", entry.source, entry.target);
+    if entry.model.is_empty() {
+        println!("Provider:    {}", entry.provider);
+    } else {
+        println!("Provider:    {} ({})", entry.provider, entry.model);
+    }
+    println!("Status:      {} {:?}", entry.status.symbol(), entry.status);
+    if let Some(error) = &entry.error {
+        println!("Error:       {}", error);
+    }
+    println!("\n{}\n\n{}", entry.original, entry.translation);
+}
+
+/// 单行显示：换行替换为空格，按显示宽度截断
+fn truncate(text: &str, width: usize) -> String {
+    let mut result = String::new();
+    let mut used = 0;
+    for c in text.chars().map(|c| if c == '\n' { ' ' } else { c }) {
+        let char_width = c.width().unwrap_or(0);
+        if used + char_width > width {
+            result.push('…');
+            break;
+        }
+        result.push(c);
+        used += char_width;
+    }
+    result
+}
+
+/// 时长：数字加单位 s/m/h/d/w
+fn parse_age(s: &str) -> Result<chrono::Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: i64 = number
+        .parse()
+        .map_err(|_| format!("invalid age '{}' (expected e.g. 30d, 12h, 2w)", s))?;
+    let age = match unit {
+        "s" => chrono::Duration::try_seconds(number),
+        "m" => chrono::Duration::try_minutes(number),
+        "h" => chrono::Duration::try_hours(number),
+        "d" | "" => chrono::Duration::try_days(number),
+        "w" => chrono::Duration::try_weeks(number),
+        _ => return Err(format!("invalid unit '{}' in age (use s, m, h, d or w)", unit)),
+    };
+    // 还须能从当前时间减去
+    age.filter(|age| Utc::now().checked_sub_signed(*age).is_some())
+        .ok_or_else(|| format!("age '{}' is too large", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ages_with_units() {
+        assert_eq!(parse_age("90s"), Ok(chrono::Duration::seconds(90)));
+        assert_eq!(parse_age("15m"), Ok(chrono::Duration::minutes(15)));
+        assert_eq!(parse_age("12h"), Ok(chrono::Duration::hours(12)));
+        assert_eq!(parse_age("30d"), Ok(chrono::Duration::days(30)));
+        assert_eq!(parse_age("30"), Ok(chrono::Duration::days(30)));
+        assert_eq!(parse_age(" 2w "), Ok(chrono::Duration::weeks(2)));
+    }
+
+    #[test]
+    fn rejects_invalid_ages() {
+        assert!(parse_age("d").unwrap_err().starts_with("invalid age"));
+        assert!(parse_age("-3d").unwrap_err().starts_with("invalid age"));
+        assert!(parse_age("3y").unwrap_err().starts_with("invalid unit 'y'"));
+    }
+
+    #[test]
+    fn rejects_ages_that_overflow() {
+        assert_eq!(parse_age("9999999999999999w"), Err("age '9999999999999999w' is too large".to_string()));
+        assert_eq!(parse_age("9223372036854775807s"), Err("age '9223372036854775807s' is too large".to_string()));
+        assert!(parse_age("99999999d").is_err());
+    }
+}
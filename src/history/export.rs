@@ -0,0 +1,239 @@
+use super::HistoryEntry;
+use anyhow::{Context, Result};
+use chrono::Local;
+use std::path::Path;
+
+/// 导出格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// Bilingual Markdown table
+    #[value(alias = "md")]
+    Markdown,
+    /// JSON lines with every field
+    #[value(alias = "jsonl")]
+    Json,
+    /// CSV: original, translation, provider, timestamp
+    Csv,
+    /// Self-contained bilingual HTML page
+    #[value(alias = "htm")]
+    Html,
+}
+
+impl ExportFormat {
+    pub const ALL: [Self; 4] = [Self::Markdown, Self::Json, Self::Csv, Self::Html];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "jsonl",
+            Self::Csv => "csv",
+            Self::Html => "html",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Markdown => "Markdown",
+            Self::Json => "JSON lines",
+            Self::Csv => "CSV",
+            Self::Html => "HTML",
+        }
+    }
+
+    /// 按文件扩展名推断格式
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        Self::ALL.into_iter().find(|format| match format {
+            Self::Markdown => extension == "md" || extension == "markdown",
+            Self::Json => extension == "jsonl" || extension == "json",
+            Self::Html => extension == "html" || extension == "htm",
+            Self::Csv => extension == "csv",
+        })
+    }
+
+    pub fn render(self, entries: &[HistoryEntry]) -> Result<String> {
+        Ok(match self {
+            Self::Markdown => markdown(entries),
+            Self::Json => json_lines(entries)?,
+            Self::Csv => csv(entries),
+            Self::Html => html(entries),
+        })
+    }
+}
+
+/// 导出到文件
+pub fn write(path: &Path, format: ExportFormat, entries: &[HistoryEntry]) -> Result<()> {
+    let content = format.render(entries)?;
+    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn time(entry: &HistoryEntry) -> String {
+    entry.timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn markdown(entries: &[HistoryEntry]) -> String {
+    // 表格单元格内不能换行，也要转义竖线；尖括号与&转义后不会被当作HTML
+    let cell = |text: &str| {
+        text.trim()
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('|', "\\|")
+            .replace("\r\n", "\n")
+            .replace(['\r', '\n'], "<br>")
+    };
+
+    let mut out = String::from("# Translations\n\n| # | Original | Translation | Provider | Time |\n|---|---|---|---|---|\n");
+    for (index, entry) in entries.iter().enumerate() {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            index + 1,
+            cell(&entry.original),
+            cell(&entry.translation),
+            cell(&entry.provider),
+            time(entry)
+        ));
+    }
+    out
+}
+
+fn json_lines(entries: &[HistoryEntry]) -> Result<String> {
+    let mut out = String::new();
+    for entry in entries {
+        out.push_str(&serde_json::to_string(entry)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn csv(entries: &[HistoryEntry]) -> String {
+    // RFC 4180：含逗号、引号或换行的字段加引号，引号加倍
+    let field = |text: &str| {
+        if text.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        } else {
+            text.to_string()
+        }
+    };
+
+    let mut out = String::from("original,translation,provider,timestamp\r\n");
+    for entry in entries {
+        out.push_str(&format!(
+            "{},{},{},{}\r\n",
+            field(&entry.original),
+            field(&entry.translation),
+            field(&entry.provider),
+            entry.timestamp.to_rfc3339()
+        ));
+    }
+    out
+}
+
+fn html(entries: &[HistoryEntry]) -> String {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+
+    // 自动检测的语言不标注
+    let lang = |code: &str| match code {
+        "auto" => String::new(),
+        code => format!(" lang=\"{}\"", escape(code)),
+    };
+
+    let mut rows = String::new();
+    for entry in entries {
+        rows.push_str(&format!(
+            "<tr><td{}>{}</td><td{}>{}</td><td class=\"meta\">{}<br>{}</td></tr>\n",
+            lang(&entry.source),
+            escape(&entry.original),
+            lang(&entry.target),
+            escape(&entry.translation),
+            escape(&entry.provider),
+            time(entry)
+        ));
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Translations</title>
+<style>
+body {{ font-family: system-ui, sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border: 1px solid #ccc; padding: 0.5em; vertical-align: top; white-space: pre-wrap; }}
+th {{ background: #f4f4f4; text-align: left; }}
+td.meta {{ color: #888; font-size: 0.85em; white-space: nowrap; }}
+</style>
+</head>
+<body>
+<h1>Translations</h1>
+<table>
+<tr><th>Original</th><th>Translation</th><th></th></tr>
+{}</table>
+</body>
+</html>
+"#,
+        rows
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{fake::FakeProvider, LanguagePair, Responder};
+    use chrono::Utc;
+
+    fn entry(original: &str, translation: &str) -> HistoryEntry {
+        let responder = Responder::of(&FakeProvider::new("Fake", vec![]));
+        let result = Ok(translation.to_string());
+        HistoryEntry::quick("session", &responder, &LanguagePair::default(), original, Utc::now(), &result)
+    }
+
+    #[test]
+    fn csv_quotes_fields_with_separators() {
+        let entries = [entry("a, b", "say \"hi\""), entry("line\nbreak", "plain")];
+        let out = csv(&entries);
+        let lines: Vec<&str> = out.split("\r\n").collect();
+        assert_eq!(lines[0], "original,translation,provider,timestamp");
+        assert!(lines[1].starts_with("\"a, b\",\"say \"\"hi\"\"\",Fake,"));
+        assert!(lines[2].starts_with("\"line\nbreak\",plain,Fake,"));
+        assert_eq!(lines[3], "");
+    }
+
+    #[test]
+    fn html_escapes_text_and_language_codes() {
+        let mut entry = entry("<b>Tom & \"Jerry\"</b>", "<script>x</script>");
+        entry.target = "ja\"><x".to_string();
+        let out = html(&[entry]);
+        assert!(out.contains("<td>&lt;b&gt;Tom &amp; &quot;Jerry&quot;&lt;/b&gt;</td>"));
+        assert!(out.contains("<td lang=\"ja&quot;&gt;&lt;x\">&lt;script&gt;x&lt;/script&gt;</td>"));
+        assert!(!out.contains("<script>"));
+    }
+
+    #[test]
+    fn markdown_cells_stay_on_one_line() {
+        let out = markdown(&[entry("a | b\nc", "d")]);
+        assert!(out.contains("| 1 | a \\| b<br>c | d | Fake |"));
+
+        let out = markdown(&[entry("one\r\ntwo\rthree", "d")]);
+        assert!(out.contains("| 1 | one<br>two<br>three | d | Fake |"));
+    }
+
+    #[test]
+    fn markdown_escapes_html() {
+        let out = markdown(&[entry("<b>bold</b> & <script>", "a<br>b")]);
+        assert!(out.contains("| 1 | &lt;b&gt;bold&lt;/b&gt; &amp; &lt;script&gt; | a&lt;br&gt;b | Fake |"));
+    }
+
+    #[test]
+    fn detects_format_from_extension() {
+        assert_eq!(ExportFormat::from_path(Path::new("out.csv")), Some(ExportFormat::Csv));
+        assert_eq!(ExportFormat::from_path(Path::new("out.HTM")), Some(ExportFormat::Html));
+        assert_eq!(ExportFormat::from_path(Path::new("out.txt")), None);
+    }
+}
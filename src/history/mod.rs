@@ -0,0 +1,6 @@
+pub mod command;
+pub mod export;
+pub mod store;
+
+pub use export::ExportFormat;
+pub use store::{EntryStatus, HistoryEntry, HistoryStore};
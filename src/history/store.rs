@@ -0,0 +1,355 @@
+use crate::config::Config;
+use crate::providers::{LanguagePair, Responder};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// 翻译结束时的状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryStatus {
+    Success,
+    Error,
+    Cancelled,
+}
+
+impl EntryStatus {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Success => "✓",
+            Self::Error => "✗",
+            Self::Cancelled => "⊘",
+        }
+    }
+}
+
+/// 历史记录中的一条翻译
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: u64,
+    /// 所属TUI/快速模式会话
+    pub session: String,
+    /// 提交时间
+    pub timestamp: DateTime<Utc>,
+    /// 结束时间
+    pub finished: DateTime<Utc>,
+    pub source: String,
+    pub target: String,
+    pub provider: String,
+    #[serde(default)]
+    pub model: String,
+    pub original: String,
+    pub translation: String,
+    pub status: EntryStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HistoryEntry {
+    /// 新会话的标识：启动时间与进程号
+    pub fn new_session() -> String {
+        format!("{}-{}", Utc::now().format("%Y%m%d%H%M%S"), std::process::id())
+    }
+
+    /// 快速模式的一次翻译；responder为实际应答的Provider（失败时为链中首个）
+    pub fn quick(
+        session: &str,
+        responder: &Responder,
+        languages: &LanguagePair,
+        original: &str,
+        started: DateTime<Utc>,
+        result: &Result<String>,
+    ) -> Self {
+        let (translation, status, error) = match result {
+            Ok(translation) => (translation.clone(), EntryStatus::Success, None),
+            Err(e) => (String::new(), EntryStatus::Error, Some(e.to_string())),
+        };
+        Self {
+            id: 0,
+            session: session.to_string(),
+            timestamp: started,
+            finished: Utc::now(),
+            source: languages.source.to_string(),
+            target: languages.resolved_target().to_string(),
+            provider: responder.name.clone(),
+            model: responder.model.clone(),
+            original: original.to_string(),
+            translation,
+            status,
+            error,
+        }
+    }
+}
+
+/// 追加写入的JSONL历史文件；写入与清理时持有文件锁，多个进程可同时使用
+///
+/// 锁文件中保存下一个id，清理记录后也不会重复使用已分配过的id
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    /// 默认位置：数据目录下的 history.jsonl
+    pub fn default_path() -> Option<PathBuf> {
+        Config::data_dir().map(|dir| dir.join("history.jsonl"))
+    }
+
+    pub fn open_default() -> Result<Self> {
+        let path = Self::default_path().context("Cannot determine the data directory")?;
+        Self::open(&path)
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 读取全部记录（按写入顺序）；无法解析的行跳过
+    pub fn load(&self) -> Result<Vec<HistoryEntry>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to open {}", self.path.display())),
+        };
+
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.with_context(|| format!("Failed to read {}", self.path.display()))?;
+            if let Ok(entry) = serde_json::from_str(&line) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// 追加一条记录，返回在锁内分配的id
+    pub fn append(&self, mut entry: HistoryEntry) -> Result<u64> {
+        let mut lock = self.lock()?;
+        entry.id = match read_next_id(&mut lock)? {
+            Some(id) => id,
+            None => next_id(&self.load()?),
+        };
+        // 先记下id再写入记录，写入失败只会跳过一个id
+        write_next_id(&mut lock, entry.id + 1)?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(entry.id)
+    }
+
+    /// 最近一次会话的全部记录
+    pub fn last_session(&self) -> Result<Vec<HistoryEntry>> {
+        let mut entries = self.load()?;
+        let Some(session) = entries.last().map(|entry| entry.session.clone()) else {
+            return Ok(Vec::new());
+        };
+        entries.retain(|entry| entry.session == session);
+        Ok(entries)
+    }
+
+    /// 删除早于cutoff的记录，返回删除条数
+    pub fn prune(&self, cutoff: DateTime<Utc>) -> Result<usize> {
+        let mut lock = self.lock()?;
+        let entries = self.load()?;
+        // 旧版本的锁文件没有id，删除前按现有记录补上
+        if read_next_id(&mut lock)?.is_none() {
+            write_next_id(&mut lock, next_id(&entries))?;
+        }
+        let (keep, removed): (Vec<_>, Vec<_>) = entries.into_iter().partition(|entry| entry.timestamp >= cutoff);
+        if removed.is_empty() {
+            return Ok(0);
+        }
+
+        // 先写临时文件再替换，避免中途失败丢失记录
+        let tmp = self.path.with_extension("jsonl.tmp");
+        let mut content = String::new();
+        for entry in &keep {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+        std::fs::write(&tmp, content).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &self.path).with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(removed.len())
+    }
+
+    /// 独占锁（释放File时解锁）；锁在单独的文件上，清理时替换历史文件不影响它
+    fn lock(&self) -> Result<File> {
+        self.ensure_dir()?;
+        let mut path = self.path.clone().into_os_string();
+        path.push(".lock");
+        let path = PathBuf::from(path);
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        file.lock().with_context(|| format!("Failed to lock {}", path.display()))?;
+        Ok(file)
+    }
+
+    fn ensure_dir(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// 按已有记录推算的下一个id
+fn next_id(entries: &[HistoryEntry]) -> u64 {
+    entries.iter().map(|entry| entry.id + 1).max().unwrap_or(1)
+}
+
+/// 锁文件中保存的下一个id；为空或无法解析时返回None
+fn read_next_id(lock: &mut File) -> Result<Option<u64>> {
+    let mut content = String::new();
+    lock.seek(SeekFrom::Start(0))?;
+    lock.read_to_string(&mut content).context("Failed to read the history lock file")?;
+    Ok(content.trim().parse().ok())
+}
+
+fn write_next_id(lock: &mut File, id: u64) -> Result<()> {
+    lock.set_len(0)?;
+    lock.seek(SeekFrom::Start(0))?;
+    writeln!(lock, "{}", id).context("Failed to write the history lock file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::fake::FakeProvider;
+
+    /// 测试用的独立历史文件
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ai-tran-cli-test-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("history.jsonl")
+    }
+
+    fn entry(original: &str) -> HistoryEntry {
+        let responder = Responder::of(&FakeProvider::new("Fake", vec![]));
+        let result = Ok(original.to_uppercase());
+        HistoryEntry::quick("session", &responder, &LanguagePair::default(), original, Utc::now(), &result)
+    }
+
+    fn ids(store: &HistoryStore) -> Vec<u64> {
+        store.load().unwrap().iter().map(|entry| entry.id).collect()
+    }
+
+    #[test]
+    fn assigns_unique_ids_across_stores() {
+        let path = temp_path("stores");
+        let first = HistoryStore::open(&path).unwrap();
+        let second = HistoryStore::open(&path).unwrap();
+
+        assert_eq!(first.append(entry("a")).unwrap(), 1);
+        assert_eq!(second.append(entry("b")).unwrap(), 2);
+        assert_eq!(first.append(entry("c")).unwrap(), 3);
+        assert_eq!(ids(&first), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn assigns_unique_ids_under_concurrent_appends() {
+        let path = temp_path("threads");
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let store = HistoryStore::open(&path).unwrap();
+                    for _ in 0..25 {
+                        store.append(entry("text")).unwrap();
+                    }
+                })
+            })
+            .collect();
+        handles.into_iter().for_each(|handle| handle.join().unwrap());
+
+        let mut ids = ids(&HistoryStore::open(&path).unwrap());
+        ids.sort_unstable();
+        assert_eq!(ids, (1..=100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn continues_ids_after_prune() {
+        let path = temp_path("prune");
+        let store = HistoryStore::open(&path).unwrap();
+        let mut old = entry("old");
+        old.timestamp = Utc::now() - chrono::Duration::days(10);
+        store.append(old).unwrap();
+        store.append(entry("new")).unwrap();
+
+        let pruned = HistoryStore::open(&path).unwrap();
+        assert_eq!(pruned.prune(Utc::now() - chrono::Duration::days(1)).unwrap(), 1);
+        assert_eq!(store.append(entry("newer")).unwrap(), 3);
+        assert_eq!(ids(&store), vec![2, 3]);
+    }
+
+    #[test]
+    fn never_reuses_ids_after_pruning_everything() {
+        let path = temp_path("prune-all");
+        let store = HistoryStore::open(&path).unwrap();
+        store.append(entry("a")).unwrap();
+        store.append(entry("b")).unwrap();
+
+        assert_eq!(store.prune(Utc::now() + chrono::Duration::days(1)).unwrap(), 2);
+        assert!(ids(&store).is_empty());
+        assert_eq!(HistoryStore::open(&path).unwrap().append(entry("c")).unwrap(), 3);
+    }
+
+    #[test]
+    fn never_reuses_the_newest_id() {
+        let path = temp_path("prune-newest");
+        let store = HistoryStore::open(&path).unwrap();
+        store.append(entry("a")).unwrap();
+        store.append(entry("b")).unwrap();
+
+        // 只保留第一条，模拟删除最新的记录
+        let first = std::fs::read_to_string(&path).unwrap().lines().next().unwrap().to_string();
+        std::fs::write(&path, format!("{}\n", first)).unwrap();
+        assert_eq!(store.append(entry("c")).unwrap(), 3);
+        assert_eq!(ids(&store), vec![1, 3]);
+    }
+
+    #[test]
+    fn continues_ids_of_files_without_a_counter() {
+        let path = temp_path("legacy");
+        let store = HistoryStore::open(&path).unwrap();
+        store.append(entry("a")).unwrap();
+        store.append(entry("b")).unwrap();
+        std::fs::write(path.with_extension("jsonl.lock"), "").unwrap();
+
+        assert_eq!(store.prune(Utc::now() + chrono::Duration::days(1)).unwrap(), 2);
+        assert_eq!(store.append(entry("c")).unwrap(), 3);
+        assert_eq!(std::fs::read_to_string(path.with_extension("jsonl.lock")).unwrap(), "4\n");
+    }
+
+    #[test]
+    fn records_session_and_responder() {
+        let responder = Responder {
+            name: "Backup".to_string(),
+            model: "backup-model".to_string(),
+        };
+        let result = Err(anyhow::anyhow!("failed"));
+        let entry = HistoryEntry::quick("run-1", &responder, &LanguagePair::default(), "text", Utc::now(), &result);
+
+        assert_eq!(entry.session, "run-1");
+        assert_eq!((entry.provider.as_str(), entry.model.as_str()), ("Backup", "backup-model"));
+        assert_eq!((entry.status, entry.error.as_deref()), (EntryStatus::Error, Some("failed")));
+    }
+}
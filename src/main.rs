@@ -1,19 +1,29 @@
 mod app;
+mod config;
+mod document;
 mod events;
+mod history;
 mod providers;
 mod ui;
+mod utils;
 
-use anyhow::Result;
-use app::App;
-use clap::Parser;
+use anyhow::{Context, Result};
+use app::{App, DisplayMode};
+use clap::{Parser, Subcommand};
 use crossterm::{
-    event::{self, Event},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{self, Event, KeyEventKind},
+    terminal::{enable_raw_mode, supports_keyboard_enhancement},
 };
-use providers::{openai::OpenAIProvider, TranslationProvider};
+use config::Config;
+use providers::{registry, Language, LanguagePair, NamedStream, Responder, TranslationProvider};
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::{io::{self, IsTerminal, Read}, sync::Arc, time::Duration};
+use std::{
+    io::{self, IsTerminal, Read},
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    sync::Arc,
+    time::Duration,
+};
 
 #[derive(Parser, Debug)]
 #[command(name = "ai-tran-cli")]
@@ -27,8 +37,81 @@ struct Args {
     #[arg(short = 'v', long = "verbose")]
     verbose: bool,
 
+    /// Provider name (see `ai-tran-cli list`) [default: openai]
+    #[arg(short = 'p', long = "provider", global = true)]
+    provider: Option<String>,
+
+    /// Named profile from the config file
+    #[arg(short = 'P', long = "profile", global = true)]
+    profile: Option<String>,
+
+    /// Display mode: bilingual, translation_only, original_only
+    #[arg(short = 'm', long = "mode")]
+    mode: Option<DisplayMode>,
+
+    /// Config file path [default: ~/.config/ai-tran-cli/config.toml]
+    #[arg(short = 'c', long = "config", global = true)]
+    config: Option<PathBuf>,
+
+    /// Source language code, e.g. en, ja, auto [default: auto]
+    #[arg(short = 's', long = "source", global = true)]
+    source: Option<Language>,
+
+    /// Target language code, e.g. zh-CN, ja, de, auto [default: auto]
+    #[arg(short = 't', long = "target", global = true)]
+    target: Option<Language>,
+
+    /// Reload the last session's translations into the TUI
+    #[arg(long)]
+    resume: bool,
+
+    /// Compare mode: translate with several providers side by side
+    /// (--compare=openai,claude:claude-haiku-4-5 or [compare] providers)
+    #[arg(long = "compare", value_name = "PROVIDERS", value_delimiter = ',', num_args = 0..=1, require_equals = true)]
+    compare: Option<Vec<String>>,
+
+    /// Read the text to translate from a file (`.md` files enable --markdown)
+    #[arg(long, value_name = "PATH", conflicts_with = "text")]
+    file: Option<PathBuf>,
+
+    /// Translate Markdown text only, keeping code, URLs, HTML and front matter intact
+    #[arg(long, conflicts_with = "lines")]
+    markdown: bool,
+
+    /// Translate each input line separately and print results in order (implies --quick)
+    #[arg(long, conflicts_with = "compare")]
+    lines: bool,
+
+    /// Print the translation as it streams in quick mode [default: when stdout is a terminal]
+    #[arg(long, overrides_with = "no_stream")]
+    stream: bool,
+
+    /// Wait for the full translation before printing in quick mode
+    #[arg(long, overrides_with = "stream")]
+    no_stream: bool,
+
+    /// Number of lines translated concurrently with --lines
+    #[arg(short = 'j', long = "jobs", default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+    jobs: u16,
+
     /// Text to translate (optional, can also use stdin)
     text: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List available providers
+    List,
+    /// Write settings to the config file, or show/check the effective configuration
+    Config(config::command::ConfigArgs),
+    /// Browse, search, export or prune the translation history
+    History {
+        #[command(subcommand)]
+        command: history::command::HistoryCommand,
+    },
 }
 
 // Global verbose flag
@@ -40,20 +123,6 @@ fn verbose_log(msg: impl AsRef<str>) {
     }
 }
 
-fn mask_sensitive(s: &str, prefix: usize, suffix: usize) -> String {
-    if s.len() <= prefix + suffix {
-        return "*".repeat(s.len());
-    }
-    let visible_len = prefix + suffix;
-    let mask_len = s.len() - visible_len;
-    format!(
-        "{}{}{}",
-        &s[..prefix],
-        "*".repeat(mask_len),
-        &s[s.len() - suffix..]
-    )
-}
-
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse command-line arguments
@@ -66,27 +135,146 @@ async fn main() -> Result<()> {
     verbose_log("Loading environment variables from .env file");
     dotenv::dotenv().ok();
 
-    // Initialize provider
-    verbose_log("Initializing OpenAI provider");
-    let provider = Arc::new(OpenAIProvider::from_env(args.verbose)?);
+    if let Some(Command::Config(config_args)) = &args.command {
+        let ctx = config::command::ConfigContext {
+            path: args.config.as_deref(),
+            provider: args.provider.as_deref(),
+            profile: args.profile.as_deref(),
+            source: args.source.as_ref(),
+            target: args.target.as_ref(),
+        };
+        return config::command::run(config_args, &ctx).await;
+    }
+
+    // Load config file
+    let mut config = Config::load(args.config.as_deref())?;
+    if let Some(mode) = args.mode {
+        config.display.mode = mode;
+    }
 
-    if args.verbose {
-        verbose_log(format!("Provider: {}", provider.name()));
-        verbose_log(format!("API Base: {}", provider.get_api_base()));
-        verbose_log(format!("Model: {}", provider.get_model()));
-        verbose_log(format!("API Key: {}", mask_sensitive(provider.get_api_key(), 7, 4)));
-        verbose_log(format!("Target Language: {}", provider.get_target_language()));
+    if let Some(Command::History { command }) = &args.command {
+        return history::command::run(command);
+    }
+
+    // Profile: CLI > default_profile
+    let (profile_name, profile) = match args.profile.as_ref().or(config.default_profile.as_ref()) {
+        Some(name) => {
+            let (name, profile) = config.profile(name)?;
+            (Some(name.to_string()), Some(profile.clone()))
+        }
+        None => (None, None),
+    };
+    if let Some(name) = &profile_name {
+        verbose_log(format!("Profile: {}", name));
+    }
+
+    // Provider: CLI > profile > config > openai
+    let provider_name = config.provider_name(args.provider.as_deref(), profile.as_ref());
+
+    if let Some(Command::List) = args.command {
+        list_providers(&provider_name, &config);
+        return Ok(());
     }
 
-    // Get input text from stdin or argument
+    // Resolve language pair: CLI > profile > env > config > auto
+    let languages = config.languages(args.source.clone(), args.target.clone(), profile.as_ref())?;
+    verbose_log(format!("Source Language: {}", languages.source));
+    verbose_log(format!("Target Language: {}", languages.target));
+
+    // Get input text from a file, stdin or argument
     let input_text = get_input_text(&args)?;
+    let markdown = args.markdown || args.file.as_deref().is_some_and(document::markdown::is_markdown_path);
+    if markdown {
+        verbose_log("Markdown mode");
+    }
+
+    if let Some(entries) = &args.compare {
+        if !entries.is_empty() {
+            config.compare.providers = entries.clone();
+        }
+    }
+
+    // Initialize provider (with fallback chain and retries)
+    verbose_log(format!("Initializing {} provider", provider_name));
+    let fallback = config.fallback(profile.as_ref());
+    if !fallback.is_empty() {
+        verbose_log(format!("Fallback: {}", fallback.join(" → ")));
+    }
 
     // Handle quick mode
+    if args.quick && args.compare.is_some() {
+        let Some(text) = input_text else {
+            eprintln!("Error: No input text provided. Use stdin or provide text as argument.");
+            std::process::exit(1);
+        };
+        return quick_compare(&config, &text, &languages, args.verbose).await;
+    }
+
+    // 本次运行的所有快速模式翻译记入同一个历史会话
+    let session = history::HistoryEntry::new_session();
+
+    if args.lines {
+        let Some(text) = input_text else {
+            eprintln!("Error: No input text provided. Use stdin or provide text as argument.");
+            std::process::exit(1);
+        };
+        let provider = providers::create_chain(&provider_name, &config, profile.as_ref(), args.verbose, None)?;
+        log_provider(provider.as_ref());
+        return quick_lines(&config, &session, provider.as_ref(), &text, &languages, args.jobs as usize).await;
+    }
+
     if args.quick {
+        let provider = providers::create_chain(&provider_name, &config, profile.as_ref(), args.verbose, None)?;
+        log_provider(provider.as_ref());
+        // DeepL、LibreTranslate等不会保留占位符，无法按Markdown结构翻译
+        let markdown = markdown && markdown_supported(provider.as_ref(), args.markdown)?;
         if let Some(text) = input_text {
-            match provider.translate(&text).await {
+            let started = chrono::Utc::now();
+            let stream = !args.no_stream && (args.stream || io::stdout().is_terminal());
+            let result = if markdown || document::needs_chunking(&text, &config.document) {
+                let chunks = if markdown {
+                    document::markdown::split(&text, config.document.chunk_tokens)
+                } else {
+                    document::split(&text, &config.document)
+                };
+                verbose_log(format!("Translating in {} chunks", chunks.len()));
+                let chunks = document::translate(Arc::clone(&provider), chunks, &languages, &config.document);
+                if stream {
+                    quick_stream(chunks).await
+                } else {
+                    collect_stream(chunks).await
+                }
+            } else if stream {
+                match provider.translate_stream_named(&text, &languages).await {
+                    Ok((responder, deltas)) => quick_stream(providers::named(responder, deltas)).await,
+                    Err(e) => Err(e),
+                }
+            } else {
+                provider
+                    .translate_named(&text, &languages, "")
+                    .await
+                    .map(|(responder, translation)| (Some(responder), translation))
+            };
+            // 实际应答的Provider（fallback链中可能不是首个）
+            let responder = match &result {
+                Ok((Some(responder), _)) => responder.clone(),
+                _ => Responder::of(provider.as_ref()),
+            };
+            let result = result.map(|(_, translation)| translation);
+            if config.history.enabled {
+                let entry = history::HistoryEntry::quick(&session, &responder, &languages, &text, started, &result);
+                if let Err(e) = history::HistoryStore::open_default().and_then(|store| store.append(entry)) {
+                    verbose_log(format!("Failed to save history: {:#}", e));
+                }
+            }
+            match result {
                 Ok(translation) => {
-                    println!("{}", translation);
+                    if !stream {
+                        println!("{}", translation);
+                    }
+                    if config.clipboard.quick_mode_auto_copy && !utils::clipboard::copy_text(&translation) {
+                        verbose_log("Failed to copy translation to clipboard");
+                    }
                 }
                 Err(e) => {
                     eprintln!("Translation error: {}", e);
@@ -101,59 +289,61 @@ async fn main() -> Result<()> {
     }
 
     // TUI mode
-    let provider_name = provider.name().to_string();
-    let mut app = App::new(provider_name);
+    let mut app = App::new(
+        &provider_name,
+        profile.as_ref(),
+        languages,
+        config,
+        profile_name,
+        args.verbose,
+    )?;
+    log_provider(app.provider.as_ref());
+    if args.compare.is_some() {
+        app.toggle_compare_mode();
+    }
+    app.markdown = markdown && markdown_supported(app.provider.as_ref(), args.markdown)?;
+    if args.resume || app.config.history.resume {
+        match app.resume_last_session() {
+            Ok(count) => app.show_notification(format!("Resumed {} translations from the last session", count)),
+            Err(e) => app.show_notification(format!("Failed to resume history: {:#}", e)),
+        }
+    }
 
     // If input from pipe, auto-commit it
     if let Some(text) = input_text {
-        let mut message = app.create_message(text.clone());
-        message.start_streaming();
-        let msg_id = message.id;
-        app.add_message(message);
-
-        // Start translation task
-        let tx = app.tx.clone();
-        let provider_clone = Arc::clone(&provider);
-
-        tokio::spawn(async move {
-            match provider_clone.translate_stream(&text).await {
-                Ok(mut stream) => {
-                    use futures::StreamExt;
-                    while let Some(result) = stream.next().await {
-                        match result {
-                            Ok(delta) => {
-                                if !delta.is_empty() {
-                                    let _ = tx.send(app::AppMessage::TranslationDelta(msg_id, delta));
-                                }
-                            }
-                            Err(e) => {
-                                let _ = tx.send(app::AppMessage::TranslationError(msg_id, e.to_string()));
-                                return;
-                            }
-                        }
-                    }
-                    let _ = tx.send(app::AppMessage::TranslationComplete(msg_id));
-                }
-                Err(e) => {
-                    let _ = tx.send(app::AppMessage::TranslationError(msg_id, e.to_string()));
-                }
-            }
-        });
+        app.submit(text);
     }
 
     // Setup terminal
     enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
+    // 支持的终端（kitty协议）可区分 Shift+Enter
+    let enhanced = supports_keyboard_enhancement().unwrap_or(false);
+    ui::terminal::enter(enhanced)?;
+    ui::terminal::set_mouse_capture(app.config.ui.mouse)?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
+    // 切换profile等操作可能需要读取密钥（如输入age口令），期间暂时退出全屏
+    let redraw = Arc::new(AtomicBool::new(false));
+    config::keys::set_terminal_guard(Some({
+        let redraw = Arc::clone(&redraw);
+        Box::new(move |suspend| {
+            if suspend {
+                let _ = ui::terminal::leave(enhanced);
+                eprintln!("Reading API key...");
+            } else {
+                let _ = ui::terminal::enter(enhanced);
+                redraw.store(true, Ordering::SeqCst);
+            }
+        })
+    }));
+
     // Run the app
-    let result = run_app(&mut terminal, &mut app, provider).await;
+    let result = run_app(&mut terminal, &mut app, &redraw).await;
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    config::keys::set_terminal_guard(None);
+    ui::terminal::leave(enhanced)?;
     terminal.show_cursor()?;
 
     // Print any errors
@@ -164,16 +354,230 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// 流式输出译文（每个片段后flush），返回最后应答的Provider与完整译文
+async fn quick_stream(mut stream: NamedStream) -> Result<(Option<Responder>, String)> {
+    use futures::StreamExt;
+    use std::io::Write;
+
+    let mut stdout = io::stdout().lock();
+    let mut translation = String::new();
+    let mut answered = None;
+    let mut result = Ok(());
+
+    while let Some(delta) = stream.next().await {
+        match delta {
+            Ok((responder, delta)) => {
+                answered = responder.or(answered);
+                // 跳过开头的空白，与非流式输出一致
+                let delta = if translation.is_empty() { delta.trim_start() } else { &delta };
+                translation.push_str(delta);
+                stdout.write_all(delta.as_bytes())?;
+                stdout.flush()?;
+            }
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+
+    if !translation.is_empty() && !translation.ends_with('\n') {
+        writeln!(stdout)?;
+    }
+    result.map(|_| (answered, translation.trim_end().to_string()))
+}
+
+/// 等待流结束，返回最后应答的Provider与拼接后的完整译文
+async fn collect_stream(mut stream: NamedStream) -> Result<(Option<Responder>, String)> {
+    use futures::StreamExt;
+
+    let mut translation = String::new();
+    let mut answered = None;
+    while let Some(delta) = stream.next().await {
+        let (responder, delta) = delta?;
+        answered = responder.or(answered);
+        translation.push_str(&delta);
+    }
+    Ok((answered, translation.trim().to_string()))
+}
+
+/// 逐行翻译：最多jobs行并发，按输入顺序输出；空行原样保留，失败的行输出原文
+async fn quick_lines(
+    config: &Config,
+    session: &str,
+    provider: &dyn TranslationProvider,
+    text: &str,
+    languages: &LanguagePair,
+    jobs: usize,
+) -> Result<()> {
+    use futures::StreamExt;
+
+    let store = if config.history.enabled {
+        history::HistoryStore::open_default()
+            .map_err(|e| verbose_log(format!("Failed to open history: {:#}", e)))
+            .ok()
+    } else {
+        None
+    };
+
+    let mut results = futures::stream::iter(text.lines().enumerate())
+        .map(|(index, line)| async move {
+            let content = line.trim();
+            if content.is_empty() {
+                return (index, line, None);
+            }
+            let started = chrono::Utc::now();
+            let result = provider.translate_named(content, languages, "").await;
+            (index, line, Some((started, result)))
+        })
+        .buffered(jobs);
+
+    let mut failed = 0;
+    while let Some((index, line, outcome)) = results.next().await {
+        let Some((started, result)) = outcome else {
+            println!("{}", line);
+            continue;
+        };
+        let responder = match &result {
+            Ok((responder, _)) => responder.clone(),
+            Err(_) => Responder::of(provider),
+        };
+        let result = result.map(|(_, translation)| translation);
+        if let Some(store) = &store {
+            let entry = history::HistoryEntry::quick(session, &responder, languages, line.trim(), started, &result);
+            if let Err(e) = store.append(entry) {
+                verbose_log(format!("Failed to save history: {:#}", e));
+            }
+        }
+        // 保留行首缩进，译文中的换行合并为空格以保持行数一致
+        let indent = &line[..line.len() - line.trim_start().len()];
+        match result {
+            Ok(translation) => println!("{}{}", indent, join_lines(&translation)),
+            Err(e) => {
+                failed += 1;
+                eprintln!("Line {}: translation error: {}", index + 1, e);
+                println!("{}", line);
+            }
+        }
+    }
+
+    if failed > 0 {
+        eprintln!("{} line(s) failed to translate", failed);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn join_lines(text: &str) -> String {
+    text.lines().map(str::trim).filter(|line| !line.is_empty()).collect::<Vec<_>>().join(" ")
+}
+
+/// 快速模式下的对比：并发请求，按配置顺序输出各结果与耗时
+async fn quick_compare(config: &Config, text: &str, languages: &LanguagePair, verbose: bool) -> Result<()> {
+    let entries = &config.compare.providers;
+    if entries.len() < 2 {
+        eprintln!("Error: Compare mode needs at least 2 providers (--compare=a,b or [compare] providers)");
+        std::process::exit(1);
+    }
+
+    let providers = registry::create_compare(entries, config, verbose, None)?;
+    let results = futures::future::join_all(providers.iter().map(|provider| async move {
+        let started = std::time::Instant::now();
+        let result = provider.translate(text, languages).await;
+        (provider, result, started.elapsed())
+    }))
+    .await;
+
+    let mut failed = 0;
+    for (index, (provider, result, elapsed)) in results.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        println!(
+            "== {} {} ({:.2}s)",
+            provider.name(),
+            provider.model(),
+            elapsed.as_secs_f32()
+        );
+        match result {
+            Ok(translation) => println!("{}", translation),
+            Err(e) => {
+                failed += 1;
+                println!("Error: {}", e);
+            }
+        }
+    }
+
+    if failed == results.len() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn log_provider(provider: &dyn TranslationProvider) {
+    if VERBOSE.load(std::sync::atomic::Ordering::Relaxed) {
+        verbose_log(format!("Provider: {}", provider.name()));
+        verbose_log(format!("API Base: {}", provider.api_base()));
+        verbose_log(format!("Model: {}", provider.model()));
+        verbose_log(format!("API Key: {}", utils::mask_sensitive(provider.api_key(), 7, 4)));
+    }
+}
+
+fn list_providers(selected: &str, config: &Config) {
+    let selected = registry::find(selected).map(|spec| spec.name);
+
+    println!("Available providers:");
+
+    for spec in registry::PROVIDERS {
+        let settings = spec.settings(&config.provider);
+        // 可创建时以Provider实际使用的地址为准；密钥来源不在此处读取
+        let (api_base, status) = match &settings.key_source {
+            Some(source) if settings.api_key.is_empty() => {
+                (settings.api_base.clone(), format!("key from {}", source))
+            }
+            _ => match spec.build(settings.clone(), false) {
+                Ok(provider) => (provider.api_base().to_string(), "ready".to_string()),
+                Err(e) => (settings.api_base.clone(), e.to_string()),
+            },
+        };
+        let marker = if Some(spec.name) == selected { "*" } else { " " };
+        let aliases = if spec.aliases.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", spec.aliases.join(", "))
+        };
+
+        println!();
+        println!("{} {}{} - {}", marker, spec.name, aliases, spec.description);
+        println!("    Model:    {}", settings.model);
+        println!("    Base URL: {}", api_base);
+        println!("    Status:   {}", status);
+    }
+
+    println!();
+    println!("Use -p/--provider <NAME> to select a provider.");
+}
+
 fn get_input_text(args: &Args) -> Result<Option<String>> {
     // Check if text provided as argument
     if let Some(ref text) = args.text {
         return Ok(Some(text.clone()));
     }
 
+    if let Some(path) = &args.file {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        return Ok((!text.trim().is_empty()).then_some(text));
+    }
+
     // Check if stdin is piped (not a terminal)
     if !io::stdin().is_terminal() {
         let mut input = String::new();
         io::stdin().read_to_string(&mut input)?;
+        // 逐行模式保留空行与缩进
+        if args.lines {
+            return Ok((!input.trim().is_empty()).then_some(input));
+        }
         let trimmed = input.trim();
         if !trimmed.is_empty() {
             return Ok(Some(trimmed.to_string()));
@@ -183,12 +587,29 @@ fn get_input_text(args: &Args) -> Result<Option<String>> {
     Ok(None)
 }
 
-async fn run_app<B: ratatui::backend::Backend, P: TranslationProvider + 'static>(
+/// Provider能否用于Markdown模式：显式指定--markdown时报错，按扩展名自动识别时退回普通模式
+fn markdown_supported(provider: &dyn TranslationProvider, explicit: bool) -> Result<bool> {
+    if provider.supports_placeholders() {
+        return Ok(true);
+    }
+    if explicit {
+        anyhow::bail!(document::markdown::UNSUPPORTED);
+    }
+    eprintln!("Warning: {}, translating as plain text", document::markdown::UNSUPPORTED);
+    Ok(false)
+}
+
+async fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut App,
-    provider: Arc<P>,
+    redraw: &AtomicBool,
 ) -> Result<()> {
     loop {
+        // 终端曾被暂时交还（如读取密钥），整屏重绘
+        if redraw.swap(false, Ordering::SeqCst) {
+            terminal.clear()?;
+        }
+
         // Draw UI
         terminal.draw(|f| ui::render_ui(f, app))?;
 
@@ -199,8 +620,13 @@ async fn run_app<B: ratatui::backend::Backend, P: TranslationProvider + 'static>
 
         // Handle keyboard events (with timeout)
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                events::handle_key_event(key, app, Arc::clone(&provider)).await?;
+            match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => {
+                    events::handle_key_event(key, app).await?;
+                }
+                Event::Mouse(mouse) => events::handle_mouse_event(mouse, app),
+                Event::Paste(text) => events::handle_paste(text, app),
+                _ => {}
             }
         }
 
@@ -0,0 +1,299 @@
+use super::{
+    prompt::create_prompt_with_context, LanguagePair, ProviderError, StreamingResponse, TranslationProvider,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use eventsource_stream::Eventsource;
+use futures::StreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const MAX_TOKENS: u32 = 4096;
+
+#[derive(Debug, Serialize)]
+struct AnthropicMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+struct MessagesRequest {
+    model: String,
+    max_tokens: u32,
+    messages: Vec<AnthropicMessage>,
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: String,
+}
+
+pub struct AnthropicProvider {
+    client: Client,
+    api_key: String,
+    api_base: String,
+    model: String,
+    verbose: bool,
+}
+
+impl AnthropicProvider {
+    pub fn new(api_key: String, api_base: String, model: String, verbose: bool) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+            api_base,
+            model,
+            verbose,
+        }
+    }
+
+    fn verbose_log(&self, msg: impl AsRef<str>) {
+        if self.verbose {
+            eprintln!("[VERBOSE] {}", msg.as_ref());
+        }
+    }
+
+    fn build_request(&self, text: &str, languages: &LanguagePair, context: &str, stream: bool) -> MessagesRequest {
+        MessagesRequest {
+            model: self.model.clone(),
+            max_tokens: MAX_TOKENS,
+            messages: vec![AnthropicMessage {
+                role: "user".to_string(),
+                content: create_prompt_with_context(text, languages, context),
+            }],
+            stream,
+        }
+    }
+
+    async fn send(&self, request: &MessagesRequest) -> Result<reqwest::Response> {
+        let url = format!("{}/messages", self.api_base.trim_end_matches('/'));
+        self.verbose_log(format!("API URL: {}", url));
+
+        let response = self
+            .client
+            .post(&url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await?;
+
+        let status = response.status();
+        self.verbose_log(format!("HTTP Status: {}", status));
+
+        if !status.is_success() {
+            let error = ProviderError::from_response(response).await;
+            self.verbose_log(format!("Error response body: {}", error.body()));
+            return Err(error.into());
+        }
+
+        Ok(response)
+    }
+}
+
+/// 解析单个SSE事件，返回文本增量
+fn parse_stream_event(event_type: &str, data: &str) -> Result<String> {
+    let value: Value =
+        serde_json::from_str(data).map_err(|e| anyhow!("Failed to parse SSE event: {}", e))?;
+
+    // 未指定event名称时回退到data中的type字段
+    let event_type = if event_type.is_empty() || event_type == "message" {
+        value["type"].as_str().unwrap_or("")
+    } else {
+        event_type
+    };
+
+    match event_type {
+        "content_block_delta" => Ok(value["delta"]["text"].as_str().unwrap_or("").to_string()),
+        "error" => Err(anyhow!(
+            "API stream error ({}): {}",
+            value["error"]["type"].as_str().unwrap_or("unknown"),
+            value["error"]["message"].as_str().unwrap_or("")
+        )),
+        // message_start, content_block_start, ping, message_delta, message_stop ...
+        _ => Ok(String::new()),
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for AnthropicProvider {
+    async fn translate_stream(&self, text: &str, languages: &LanguagePair) -> Result<StreamingResponse> {
+        let request = self.build_request(text, languages, "", true);
+        let response = self.send(&request).await?;
+
+        let stream = response
+            .bytes_stream()
+            .eventsource()
+            // message_stop之后不再读取
+            .take_while(|event| {
+                let done = matches!(event, Ok(event) if event.event == "message_stop");
+                futures::future::ready(!done)
+            })
+            .map(|event| match event {
+                Ok(event) => parse_stream_event(&event.event, &event.data),
+                Err(e) => Err(ProviderError::Stream(e.to_string()).into()),
+            });
+
+        Ok(Box::pin(stream))
+    }
+
+    async fn translate(&self, text: &str, languages: &LanguagePair) -> Result<String> {
+        self.translate_with_context(text, languages, "").await
+    }
+
+    async fn translate_with_context(&self, text: &str, languages: &LanguagePair, context: &str) -> Result<String> {
+        self.verbose_log(format!("Translating text: {}", text));
+        self.verbose_log(format!("Languages: {}", languages));
+
+        let request = self.build_request(text, languages, context, false);
+        self.verbose_log(format!("API Model: {}", self.model));
+
+        let response = self.send(&request).await?;
+        let response_text = response.text().await?;
+        self.verbose_log(format!("Response body length: {} bytes", response_text.len()));
+
+        let completion: MessagesResponse = serde_json::from_str(&response_text)
+            .map_err(|e| anyhow!("Failed to parse response JSON: {}", e))?;
+
+        let translation: String = completion
+            .content
+            .iter()
+            .filter(|block| block.kind == "text")
+            .map(|block| block.text.as_str())
+            .collect();
+
+        if translation.is_empty() {
+            return Err(anyhow!("No text content in response"));
+        }
+
+        self.verbose_log(format!("Translation result: {}", translation));
+        Ok(translation.trim().to_string())
+    }
+
+    fn name(&self) -> &str {
+        "Anthropic"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    fn api_key(&self) -> &str {
+        &self.api_key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Language;
+    use super::*;
+    use crate::providers::mock::serve_once;
+
+    fn provider(server: String) -> AnthropicProvider {
+        AnthropicProvider::new("sk-test".to_string(), format!("{}/v1", server), "claude-test".to_string(), false)
+    }
+
+    fn languages() -> LanguagePair {
+        LanguagePair::new(Language::Auto, "ja".parse().unwrap())
+    }
+
+    #[test]
+    fn parses_text_delta() {
+        let data = r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hello"}}"#;
+        assert_eq!(parse_stream_event("content_block_delta", data).unwrap(), "Hello");
+    }
+
+    #[test]
+    fn falls_back_to_data_type_without_event_name() {
+        let data = r#"{"type":"content_block_delta","delta":{"text":"Hi"}}"#;
+        assert_eq!(parse_stream_event("", data).unwrap(), "Hi");
+        assert_eq!(parse_stream_event("message", data).unwrap(), "Hi");
+    }
+
+    #[test]
+    fn ignores_other_events() {
+        assert_eq!(parse_stream_event("ping", r#"{"type":"ping"}"#).unwrap(), "");
+        let start = r#"{"type":"message_start","message":{"id":"msg_1"}}"#;
+        assert_eq!(parse_stream_event("message_start", start).unwrap(), "");
+    }
+
+    #[test]
+    fn reports_stream_errors() {
+        let data = r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        let error = parse_stream_event("error", data).unwrap_err();
+        assert_eq!(error.to_string(), "API stream error (overloaded_error): Overloaded");
+    }
+
+    #[test]
+    fn rejects_invalid_json() {
+        let error = parse_stream_event("content_block_delta", "not json").unwrap_err();
+        assert!(error.to_string().starts_with("Failed to parse SSE event"));
+    }
+
+    #[tokio::test]
+    async fn streams_deltas_from_endpoint() {
+        let body = concat!(
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\"}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"delta\":{\"text\":\"こんにちは\"}}\n\n",
+            "event: ping\n",
+            "data: {\"type\":\"ping\"}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"delta\":{\"text\":\"世界\"}}\n\n",
+            "event: message_stop\n",
+            "data: {\"type\":\"message_stop\"}\n\n",
+        );
+        let (api_base, server) = serve_once("200 OK", "text/event-stream", body).await;
+
+        let stream = provider(api_base).translate_stream("Hello world", &languages()).await.unwrap();
+        let chunks: Vec<String> = stream.map(|chunk| chunk.unwrap()).collect().await;
+        assert_eq!(chunks.concat(), "こんにちは世界");
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /v1/messages "));
+        let headers = request.to_ascii_lowercase();
+        assert!(headers.contains("x-api-key: sk-test"));
+        assert!(headers.contains("anthropic-version: 2023-06-01"));
+        assert!(request.contains(r#""stream":true"#));
+        assert!(request.contains(r#""model":"claude-test""#));
+    }
+
+    #[tokio::test]
+    async fn translates_without_streaming() {
+        let body = r#"{"content":[{"type":"text","text":"  こんにちは  "}]}"#;
+        let (api_base, server) = serve_once("200 OK", "application/json", body).await;
+
+        let translation = provider(api_base).translate("Hello", &languages()).await.unwrap();
+        assert_eq!(translation, "こんにちは");
+        assert!(server.await.unwrap().contains(r#""stream":false"#));
+    }
+
+    #[tokio::test]
+    async fn surfaces_api_errors_with_status() {
+        let body = r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#;
+        let (api_base, server) = serve_once("401 Unauthorized", "application/json", body).await;
+
+        let error = provider(api_base).translate("Hello", &languages()).await.unwrap_err();
+        let provider_error = error.downcast_ref::<ProviderError>().unwrap();
+        assert_eq!(provider_error.status(), Some(401));
+        assert!(error.to_string().contains("invalid x-api-key"));
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,290 @@
+use super::{
+    language::looks_english, single_response, Language, LanguagePair, ProviderError, StreamingResponse,
+    TranslationProvider,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+struct TranslateRequest<'a> {
+    text: Vec<&'a str>,
+    target_lang: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_lang: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranslateResponse {
+    translations: Vec<Translation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Translation {
+    #[serde(default)]
+    detected_source_language: String,
+    text: String,
+}
+
+const PRO_API_BASE: &str = "https://api.deepl.com/v2";
+const FREE_API_BASE: &str = "https://api-free.deepl.com/v2";
+
+/// DeepL REST API（整段返回，不支持流式）
+pub struct DeepLProvider {
+    client: Client,
+    api_key: String,
+    api_base: String,
+    verbose: bool,
+}
+
+impl DeepLProvider {
+    pub fn new(api_key: String, api_base: String, verbose: bool) -> Self {
+        // Free API密钥以 ":fx" 结尾，只能访问Free端点
+        let api_base = if api_key.ends_with(":fx") && api_base.trim_end_matches('/') == PRO_API_BASE {
+            FREE_API_BASE.to_string()
+        } else {
+            api_base
+        };
+
+        Self {
+            client: Client::new(),
+            api_key,
+            api_base,
+            verbose,
+        }
+    }
+
+    fn verbose_log(&self, msg: impl AsRef<str>) {
+        if self.verbose {
+            eprintln!("[VERBOSE] {}", msg.as_ref());
+        }
+    }
+
+    async fn request(&self, text: &str, source: &Language, target: &Language) -> Result<Translation> {
+        let request = TranslateRequest {
+            text: vec![text],
+            target_lang: target_code(target),
+            source_lang: (!source.is_auto()).then(|| source_code(source)),
+        };
+
+        let url = format!("{}/translate", self.api_base.trim_end_matches('/'));
+        self.verbose_log(format!("API URL: {}", url));
+        self.verbose_log(format!(
+            "DeepL languages: {} → {}",
+            request.source_lang.as_deref().unwrap_or("auto"),
+            request.target_lang
+        ));
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("DeepL-Auth-Key {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send()
+            .await?;
+
+        let status = response.status();
+        self.verbose_log(format!("HTTP Status: {}", status));
+
+        if !status.is_success() {
+            let error = ProviderError::from_response(response).await;
+            self.verbose_log(format!("Error response body: {}", error.body()));
+            return Err(error.into());
+        }
+
+        let mut parsed: TranslateResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to parse response JSON: {}", e))?;
+
+        if parsed.translations.is_empty() {
+            return Err(anyhow!("No translations in response"));
+        }
+        Ok(parsed.translations.remove(0))
+    }
+}
+
+/// DeepL目标语言代码（EN/PT/ZH需要区分变体）
+fn target_code(language: &Language) -> String {
+    match language.code().to_ascii_lowercase().replace('_', "-").as_str() {
+        "en" | "en-us" => "EN-US".to_string(),
+        "en-gb" => "EN-GB".to_string(),
+        "pt" | "pt-br" => "PT-BR".to_string(),
+        "pt-pt" => "PT-PT".to_string(),
+        "zh" | "zh-cn" | "zh-hans" => "ZH-HANS".to_string(),
+        "zh-tw" | "zh-hk" | "zh-hant" => "ZH-HANT".to_string(),
+        code => code.to_ascii_uppercase(),
+    }
+}
+
+/// DeepL源语言代码（仅主语言）
+fn source_code(language: &Language) -> String {
+    language
+        .code()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase()
+}
+
+#[async_trait]
+impl TranslationProvider for DeepLProvider {
+    async fn translate_stream(&self, text: &str, languages: &LanguagePair) -> Result<StreamingResponse> {
+        Ok(single_response(self.translate(text, languages).await?))
+    }
+
+    async fn translate(&self, text: &str, languages: &LanguagePair) -> Result<String> {
+        self.verbose_log(format!("Translating text: {}", text));
+        self.verbose_log(format!("Languages: {}", languages));
+
+        let target = languages.resolved_target();
+        let translation = if target.is_auto() {
+            // 中英互译：按文字猜测原文是否为英文，检测结果与猜测不符时才重新请求
+            let english = Language::Code("en".to_string());
+            let chinese = Language::Code("zh-CN".to_string());
+            let guessed_english = looks_english(text);
+            let guess = if guessed_english { &chinese } else { &english };
+            let first = self.request(text, &languages.source, guess).await?;
+            let detected_english = first.detected_source_language.eq_ignore_ascii_case("EN");
+            if detected_english == guessed_english {
+                first.text
+            } else if detected_english {
+                self.request(text, &english, &chinese).await?.text
+            } else {
+                self.request(text, &languages.source, &english).await?.text
+            }
+        } else {
+            self.request(text, &languages.source, &target).await?.text
+        };
+
+        self.verbose_log(format!("Translation result: {}", translation));
+        Ok(translation.trim().to_string())
+    }
+
+    fn name(&self) -> &str {
+        "DeepL"
+    }
+
+    fn model(&self) -> &str {
+        "default"
+    }
+
+    fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    fn supports_placeholders(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock::{serve, Reply};
+
+    fn code(code: &str) -> Language {
+        code.parse().unwrap()
+    }
+
+    fn reply(detected: &str, text: &str) -> Reply {
+        let body = format!(r#"{{"translations":[{{"detected_source_language":"{}","text":"{}"}}]}}"#, detected, text);
+        Reply::new("200 OK", "application/json", &body)
+    }
+
+    /// 自动中英互译：返回译文与各次请求的目标语言
+    async fn translate_auto(text: &str, replies: Vec<Reply>) -> (String, Vec<String>) {
+        let (api_base, server) = serve(replies).await;
+        let provider = DeepLProvider::new("key".to_string(), api_base, false);
+        let translation = provider.translate(text, &LanguagePair::default()).await.unwrap();
+        let targets = server
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| request.split(r#""target_lang":""#).nth(1).unwrap().split('"').next().unwrap().to_string())
+            .collect();
+        (translation, targets)
+    }
+
+    #[test]
+    fn maps_language_codes() {
+        assert_eq!(target_code(&code("en")), "EN-US");
+        assert_eq!(target_code(&code("en_GB")), "EN-GB");
+        assert_eq!(target_code(&code("pt")), "PT-BR");
+        assert_eq!(target_code(&code("pt-PT")), "PT-PT");
+        assert_eq!(target_code(&code("zh-CN")), "ZH-HANS");
+        assert_eq!(target_code(&code("zh-TW")), "ZH-HANT");
+        assert_eq!(target_code(&code("ja")), "JA");
+
+        assert_eq!(source_code(&code("en-GB")), "EN");
+        assert_eq!(source_code(&code("zh_TW")), "ZH");
+        assert_eq!(source_code(&code("de")), "DE");
+    }
+
+    #[test]
+    fn free_keys_use_the_free_endpoint() {
+        let free = DeepLProvider::new("key:fx".to_string(), format!("{}/", PRO_API_BASE), false);
+        assert_eq!(free.api_base(), FREE_API_BASE);
+        let pro = DeepLProvider::new("key".to_string(), PRO_API_BASE.to_string(), false);
+        assert_eq!(pro.api_base(), PRO_API_BASE);
+        // 自定义端点不改写
+        let custom = DeepLProvider::new("key:fx".to_string(), "http://localhost:9000/v2".to_string(), false);
+        assert_eq!(custom.api_base(), "http://localhost:9000/v2");
+    }
+
+    #[tokio::test]
+    async fn free_key_is_sent_to_a_custom_endpoint() {
+        let (api_base, server) = serve(vec![reply("EN", "Hallo")]).await;
+        let provider = DeepLProvider::new("key:fx".to_string(), api_base, false);
+        let languages = LanguagePair::new(code("en"), code("de"));
+
+        assert_eq!(provider.translate("Hello", &languages).await.unwrap(), "Hallo");
+        let request = server.await.unwrap().remove(0);
+        assert!(request.starts_with("POST /translate "));
+        assert!(request.to_ascii_lowercase().contains("authorization: deepl-auth-key key:fx"));
+        assert!(request.contains(r#""target_lang":"DE","source_lang":"EN""#));
+    }
+
+    #[tokio::test]
+    async fn auto_translates_english_to_chinese_in_one_request() {
+        let (translation, targets) = translate_auto("Hello world", vec![reply("EN", "你好世界")]).await;
+        assert_eq!(translation, "你好世界");
+        assert_eq!(targets, vec!["ZH-HANS"]);
+    }
+
+    #[tokio::test]
+    async fn auto_translates_chinese_to_english_in_one_request() {
+        let (translation, targets) = translate_auto("你好世界", vec![reply("ZH", "Hello world")]).await;
+        assert_eq!(translation, "Hello world");
+        assert_eq!(targets, vec!["EN-US"]);
+    }
+
+    #[tokio::test]
+    async fn auto_retries_when_detection_contradicts_the_guess() {
+        // 看似英文的法语：改译为英文
+        let replies = vec![reply("FR", "你好世界"), reply("FR", "Hello world")];
+        let (translation, targets) = translate_auto("Bonjour le monde", replies).await;
+        assert_eq!(translation, "Hello world");
+        assert_eq!(targets, vec!["ZH-HANS", "EN-US"]);
+
+        // 含非ASCII字符的英文：改译为中文
+        let replies = vec![reply("EN", "Café time"), reply("EN", "咖啡时间")];
+        let (translation, targets) = translate_auto("Café time", replies).await;
+        assert_eq!(translation, "咖啡时间");
+        assert_eq!(targets, vec!["EN-US", "ZH-HANS"]);
+    }
+
+    #[tokio::test]
+    async fn empty_translations_are_an_error() {
+        let (api_base, _server) = serve(vec![Reply::new("200 OK", "application/json", r#"{"translations":[]}"#)]).await;
+        let provider = DeepLProvider::new("key".to_string(), api_base, false);
+        let error = provider.translate("Hello", &LanguagePair::default()).await.unwrap_err();
+        assert_eq!(error.to_string(), "No translations in response");
+    }
+}
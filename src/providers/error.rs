@@ -0,0 +1,124 @@
+use std::time::Duration;
+use thiserror::Error;
+
+/// Provider请求错误（保留HTTP状态码，便于分类处理）
+#[derive(Debug, Error)]
+pub enum ProviderError {
+    #[error("API request failed ({status} {reason}): {body}")]
+    Api {
+        status: u16,
+        reason: String,
+        body: String,
+        /// 响应头 Retry-After
+        retry_after: Option<Duration>,
+    },
+
+    /// 流式响应传输中断
+    #[error("Stream error: {0}")]
+    Stream(String),
+}
+
+impl ProviderError {
+    pub fn api(status: reqwest::StatusCode, body: String) -> Self {
+        Self::Api {
+            status: status.as_u16(),
+            reason: status.canonical_reason().unwrap_or("Unknown").to_string(),
+            body,
+            retry_after: None,
+        }
+    }
+
+    /// 从失败的响应构造，读取 Retry-After 与响应体
+    pub async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
+        let body = response.text().await.unwrap_or_default();
+
+        let mut error = Self::api(status, body);
+        if let Self::Api { retry_after: slot, .. } = &mut error {
+            *slot = retry_after;
+        }
+        error
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Api { status, .. } => Some(*status),
+            Self::Stream(_) => None,
+        }
+    }
+
+    pub fn body(&self) -> &str {
+        match self {
+            Self::Api { body, .. } => body,
+            Self::Stream(message) => message,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Api { retry_after, .. } => *retry_after,
+            Self::Stream(_) => None,
+        }
+    }
+
+    /// 限流、服务端错误与传输中断可重试
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Api { status, .. } => matches!(status, 408 | 429 | 500 | 502 | 503 | 504 | 529),
+            Self::Stream(_) => true,
+        }
+    }
+}
+
+/// Retry-After：秒数或HTTP日期
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let seconds = (date.timestamp() - chrono::Utc::now().timestamp()).max(0);
+    Some(Duration::from_secs(seconds as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_retry_after_seconds() {
+        assert_eq!(parse_retry_after("5"), Some(Duration::from_secs(5)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn parses_retry_after_http_date() {
+        let date = (chrono::Utc::now() + chrono::Duration::seconds(30)).to_rfc2822();
+        let delay = parse_retry_after(&date).unwrap();
+        assert!(delay <= Duration::from_secs(30) && delay >= Duration::from_secs(28));
+
+        // 已过去的时间不等待
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn rejects_invalid_retry_after() {
+        assert_eq!(parse_retry_after("soon"), None);
+        assert_eq!(parse_retry_after("-1"), None);
+    }
+
+    #[test]
+    fn classifies_retryable_statuses() {
+        let error = |status| ProviderError::api(reqwest::StatusCode::from_u16(status).unwrap(), String::new());
+        assert!(error(429).is_retryable());
+        assert!(error(503).is_retryable());
+        assert!(!error(400).is_retryable());
+        assert!(!error(401).is_retryable());
+        assert!(ProviderError::Stream("eof".to_string()).is_retryable());
+    }
+}
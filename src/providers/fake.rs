@@ -0,0 +1,63 @@
+use super::{single_response, LanguagePair, ProviderError, StreamingResponse, TranslationProvider};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// 测试用Provider：按顺序返回预设结果（Err为HTTP状态码），用完后回显原文
+pub struct FakeProvider {
+    name: String,
+    script: Mutex<VecDeque<Result<String, u16>>>,
+    calls: AtomicUsize,
+}
+
+impl FakeProvider {
+    pub fn new(name: &str, script: Vec<Result<&str, u16>>) -> Self {
+        Self {
+            name: name.to_string(),
+            script: Mutex::new(script.into_iter().map(|r| r.map(str::to_string)).collect()),
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for FakeProvider {
+    async fn translate_stream(&self, text: &str, languages: &LanguagePair) -> Result<StreamingResponse> {
+        Ok(single_response(self.translate(text, languages).await?))
+    }
+
+    async fn translate(&self, text: &str, _languages: &LanguagePair) -> Result<String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        match self.script.lock().unwrap().pop_front() {
+            Some(Ok(translation)) => Ok(translation),
+            Some(Err(status)) => Err(ProviderError::api(
+                reqwest::StatusCode::from_u16(status).unwrap(),
+                String::new(),
+            )
+            .into()),
+            None => Ok(text.to_string()),
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn model(&self) -> &str {
+        "fake"
+    }
+
+    fn api_base(&self) -> &str {
+        ""
+    }
+
+    fn api_key(&self) -> &str {
+        ""
+    }
+}
@@ -0,0 +1,199 @@
+use super::{first_chunk, LanguagePair, Notifier, Responder, StreamingResponse, TranslationProvider};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Provider链：前一个出错（鉴权、配额、超时、流解析失败等）时依次改用下一个
+pub struct FallbackProvider {
+    providers: Vec<Arc<dyn TranslationProvider>>,
+    verbose: bool,
+    on_fallback: Option<Notifier>,
+}
+
+impl FallbackProvider {
+    pub fn new(providers: Vec<Arc<dyn TranslationProvider>>, verbose: bool) -> Self {
+        Self {
+            providers,
+            verbose,
+            on_fallback: None,
+        }
+    }
+
+    pub fn on_fallback(mut self, callback: Notifier) -> Self {
+        self.on_fallback = Some(callback);
+        self
+    }
+
+    fn verbose_log(&self, msg: impl AsRef<str>) {
+        if self.verbose {
+            eprintln!("[VERBOSE] {}", msg.as_ref());
+        }
+    }
+
+    fn primary(&self) -> &dyn TranslationProvider {
+        self.providers[0].as_ref()
+    }
+
+    /// 记录失败；还有下一个Provider时发出通知
+    fn report(&self, index: usize, error: &anyhow::Error, failures: &mut Vec<String>) {
+        let failed = self.providers[index].name();
+        failures.push(format!("{}: {}", failed, error));
+
+        if let Some(next) = self.providers.get(index + 1) {
+            let message = format!("{} failed, falling back to {}", failed, next.name());
+            self.verbose_log(format!("{} ({})", message, error));
+            if let Some(callback) = &self.on_fallback {
+                callback(message);
+            }
+        }
+    }
+
+    /// 全部失败：保留最后一个错误（便于按类型判断是否重试），消息中列出每个Provider的错误
+    fn exhausted(error: anyhow::Error, failures: Vec<String>) -> anyhow::Error {
+        error.context(format!("All providers failed: {}", failures.join("; ")))
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for FallbackProvider {
+    async fn translate_stream(&self, text: &str, languages: &LanguagePair) -> Result<StreamingResponse> {
+        Ok(self.translate_stream_named(text, languages).await?.1)
+    }
+
+    async fn translate_stream_named(
+        &self,
+        text: &str,
+        languages: &LanguagePair,
+    ) -> Result<(Responder, StreamingResponse)> {
+        let mut failures = Vec::new();
+        let mut last_error = None;
+
+        for (index, provider) in self.providers.iter().enumerate() {
+            // 只在收到第一段内容之前切换，避免输出混杂两个Provider的结果
+            let result = match provider.translate_stream_named(text, languages).await {
+                Ok((responder, stream)) => first_chunk(stream).await.map(|stream| (responder, stream)),
+                Err(error) => Err(error),
+            };
+            match result {
+                Ok(answer) => return Ok(answer),
+                Err(error) => {
+                    self.report(index, &error, &mut failures);
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        let error = last_error.unwrap_or_else(|| anyhow!("No providers configured"));
+        Err(Self::exhausted(error, failures))
+    }
+
+    async fn translate(&self, text: &str, languages: &LanguagePair) -> Result<String> {
+        self.translate_with_context(text, languages, "").await
+    }
+
+    async fn translate_with_context(&self, text: &str, languages: &LanguagePair, context: &str) -> Result<String> {
+        Ok(self.translate_named(text, languages, context).await?.1)
+    }
+
+    async fn translate_named(
+        &self,
+        text: &str,
+        languages: &LanguagePair,
+        context: &str,
+    ) -> Result<(Responder, String)> {
+        let mut failures = Vec::new();
+        let mut last_error = None;
+
+        for (index, provider) in self.providers.iter().enumerate() {
+            match provider.translate_named(text, languages, context).await {
+                Ok((responder, translation)) => {
+                    if index > 0 {
+                        self.verbose_log(format!("Answered by {} ({})", responder.name, responder.model));
+                    }
+                    return Ok((responder, translation));
+                }
+                Err(error) => {
+                    self.report(index, &error, &mut failures);
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        let error = last_error.unwrap_or_else(|| anyhow!("No providers configured"));
+        Err(Self::exhausted(error, failures))
+    }
+
+    fn name(&self) -> &str {
+        self.primary().name()
+    }
+
+    fn model(&self) -> &str {
+        self.primary().model()
+    }
+
+    fn api_base(&self) -> &str {
+        self.primary().api_base()
+    }
+
+    fn api_key(&self) -> &str {
+        self.primary().api_key()
+    }
+
+    /// 链中任一Provider都可能应答，须全部支持
+    fn supports_placeholders(&self) -> bool {
+        self.providers.iter().all(|provider| provider.supports_placeholders())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::fake::FakeProvider;
+    use futures::StreamExt;
+
+    fn chain(providers: &[&Arc<FakeProvider>]) -> FallbackProvider {
+        let providers = providers
+            .iter()
+            .map(|provider| Arc::clone(provider) as Arc<dyn TranslationProvider>)
+            .collect();
+        FallbackProvider::new(providers, false)
+    }
+
+    #[tokio::test]
+    async fn reports_the_provider_that_answered() {
+        let primary = Arc::new(FakeProvider::new("primary", vec![Err(401)]));
+        let backup = Arc::new(FakeProvider::new("backup", vec![Ok("translated")]));
+
+        let (responder, translation) = chain(&[&primary, &backup])
+            .translate_named("text", &LanguagePair::default(), "")
+            .await
+            .unwrap();
+        assert_eq!(responder.name, "backup");
+        assert_eq!(translation, "translated");
+    }
+
+    #[tokio::test]
+    async fn reports_the_streaming_provider_that_answered() {
+        let primary = Arc::new(FakeProvider::new("primary", vec![Err(500)]));
+        let backup = Arc::new(FakeProvider::new("backup", vec![Ok("streamed")]));
+
+        let (responder, stream) = chain(&[&primary, &backup])
+            .translate_stream_named("text", &LanguagePair::default())
+            .await
+            .unwrap();
+        let deltas: Vec<String> = stream.map(|delta| delta.unwrap()).collect().await;
+        assert_eq!(responder.name, "backup");
+        assert_eq!(deltas.concat(), "streamed");
+    }
+
+    #[tokio::test]
+    async fn lists_every_failure_when_exhausted() {
+        let primary = Arc::new(FakeProvider::new("primary", vec![Err(401)]));
+        let backup = Arc::new(FakeProvider::new("backup", vec![Err(503)]));
+
+        let error = chain(&[&primary, &backup]).translate("text", &LanguagePair::default()).await.unwrap_err();
+        let message = error.to_string();
+        assert!(message.starts_with("All providers failed: primary: API request failed (401"), "{}", message);
+        assert!(message.contains("; backup: API request failed (503"), "{}", message);
+    }
+}
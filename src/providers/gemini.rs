@@ -0,0 +1,322 @@
+use super::{
+    prompt::create_prompt_with_context, LanguagePair, ProviderError, StreamingResponse, TranslationProvider,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use eventsource_stream::Eventsource;
+use futures::{future, stream, StreamExt};
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[derive(Debug, Serialize)]
+struct Part {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Content {
+    role: String,
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Serialize)]
+struct GenerateContentRequest {
+    contents: Vec<Content>,
+}
+
+pub struct GeminiProvider {
+    client: Client,
+    api_key: String,
+    api_base: String,
+    model: String,
+    verbose: bool,
+}
+
+impl GeminiProvider {
+    pub fn new(api_key: String, api_base: String, model: String, verbose: bool) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+            api_base,
+            model,
+            verbose,
+        }
+    }
+
+    fn verbose_log(&self, msg: impl AsRef<str>) {
+        if self.verbose {
+            eprintln!("[VERBOSE] {}", msg.as_ref());
+        }
+    }
+
+    fn build_request(&self, text: &str, languages: &LanguagePair, context: &str) -> GenerateContentRequest {
+        GenerateContentRequest {
+            contents: vec![Content {
+                role: "user".to_string(),
+                parts: vec![Part {
+                    text: create_prompt_with_context(text, languages, context),
+                }],
+            }],
+        }
+    }
+
+    /// method: generateContent 或 streamGenerateContent?alt=sse
+    async fn send(&self, method: &str, request: &GenerateContentRequest) -> Result<reqwest::Response> {
+        let url = format!(
+            "{}/models/{}:{}",
+            self.api_base.trim_end_matches('/'),
+            self.model,
+            method
+        );
+        self.verbose_log(format!("API URL: {}", url));
+
+        let response = self
+            .client
+            .post(&url)
+            .header("x-goog-api-key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await?;
+
+        let status = response.status();
+        self.verbose_log(format!("HTTP Status: {}", status));
+
+        if !status.is_success() {
+            let error = ProviderError::from_response(response).await;
+            self.verbose_log(format!("Error response body: {}", error.body()));
+            return Err(error.into());
+        }
+
+        Ok(response)
+    }
+}
+
+/// 从GenerateContentResponse中提取 candidates[0].content.parts[].text
+fn extract_text(value: &Value) -> Result<String> {
+    if let Some(error) = value.get("error") {
+        return Err(anyhow!(
+            "API error ({}): {}",
+            error["status"].as_str().unwrap_or("unknown"),
+            error["message"].as_str().unwrap_or("")
+        ));
+    }
+
+    if let Some(reason) = value["promptFeedback"]["blockReason"].as_str() {
+        return Err(anyhow!("Prompt blocked by Gemini: {}", reason));
+    }
+
+    // SAFETY、RECITATION等：候选被拦截，正文为空或不完整
+    let candidate = &value["candidates"][0];
+    if let Some(reason) = candidate["finishReason"].as_str() {
+        if !matches!(reason, "STOP" | "MAX_TOKENS" | "FINISH_REASON_UNSPECIFIED") {
+            return Err(anyhow!("Response blocked by Gemini: {}", reason));
+        }
+    }
+
+    let text = candidate["content"]["parts"]
+        .as_array()
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|part| part["text"].as_str())
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+
+    Ok(text)
+}
+
+#[async_trait]
+impl TranslationProvider for GeminiProvider {
+    async fn translate_stream(&self, text: &str, languages: &LanguagePair) -> Result<StreamingResponse> {
+        let request = self.build_request(text, languages, "");
+        let response = self.send("streamGenerateContent?alt=sse", &request).await?;
+
+        let stream = response
+            .bytes_stream()
+            .eventsource()
+            .map(|event| match event {
+                Ok(event) => {
+                    let parsed: Result<Value, _> = serde_json::from_str(&event.data);
+                    match parsed {
+                        Ok(value) => extract_text(&value),
+                        Err(e) => Err(anyhow!("Failed to parse SSE event: {}", e)),
+                    }
+                }
+                Err(e) => Err(ProviderError::Stream(e.to_string()).into()),
+            });
+
+        // 整个流没有任何文字时报错，而不是静默输出空译文
+        let produced = Arc::new(AtomicBool::new(false));
+        let seen = Arc::clone(&produced);
+        let stream = stream
+            .inspect(move |result| {
+                if result.as_ref().is_ok_and(|text| !text.is_empty()) {
+                    seen.store(true, Ordering::SeqCst);
+                }
+            })
+            .chain(
+                stream::once(async move {
+                    (!produced.load(Ordering::SeqCst)).then(|| Err(anyhow!("No candidates in response")))
+                })
+                .filter_map(future::ready),
+            );
+
+        Ok(Box::pin(stream))
+    }
+
+    async fn translate(&self, text: &str, languages: &LanguagePair) -> Result<String> {
+        self.translate_with_context(text, languages, "").await
+    }
+
+    async fn translate_with_context(&self, text: &str, languages: &LanguagePair, context: &str) -> Result<String> {
+        self.verbose_log(format!("Translating text: {}", text));
+        self.verbose_log(format!("Languages: {}", languages));
+        self.verbose_log(format!("API Model: {}", self.model));
+
+        let request = self.build_request(text, languages, context);
+        let response = self.send("generateContent", &request).await?;
+        let response_text = response.text().await?;
+        self.verbose_log(format!("Response body length: {} bytes", response_text.len()));
+
+        let value: Value = serde_json::from_str(&response_text)
+            .map_err(|e| anyhow!("Failed to parse response JSON: {}", e))?;
+        let translation = extract_text(&value)?;
+
+        if translation.is_empty() {
+            return Err(anyhow!("No candidates in response"));
+        }
+
+        self.verbose_log(format!("Translation result: {}", translation));
+        Ok(translation.trim().to_string())
+    }
+
+    fn name(&self) -> &str {
+        "Gemini"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    fn api_key(&self) -> &str {
+        &self.api_key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Language;
+    use super::*;
+    use crate::providers::mock::{serve, serve_once, Reply};
+    use serde_json::json;
+
+    fn provider(api_base: String) -> GeminiProvider {
+        GeminiProvider::new("key-test".to_string(), api_base, "gemini-test".to_string(), false)
+    }
+
+    fn languages() -> LanguagePair {
+        LanguagePair::new(Language::Auto, "ja".parse().unwrap())
+    }
+
+    fn event(value: Value) -> String {
+        format!("data: {}\r\n\r\n", value)
+    }
+
+    async fn stream_result(body: &[&str]) -> (Vec<Result<String>>, String) {
+        let (api_base, server) = serve(vec![Reply::split("200 OK", "text/event-stream", body)]).await;
+        let stream = provider(api_base).translate_stream("Hello", &languages()).await.unwrap();
+        let results = stream.collect().await;
+        (results, server.await.unwrap().remove(0))
+    }
+
+    #[test]
+    fn concatenates_parts() {
+        let value = json!({"candidates": [{"content": {"parts": [{"text": "こん"}, {"text": "にちは"}]}}]});
+        assert_eq!(extract_text(&value).unwrap(), "こんにちは");
+        assert_eq!(extract_text(&json!({"usageMetadata": {}})).unwrap(), "");
+    }
+
+    #[test]
+    fn reports_errors_and_blocks() {
+        let error = json!({"error": {"code": 429, "status": "RESOURCE_EXHAUSTED", "message": "Quota exceeded"}});
+        assert_eq!(extract_text(&error).unwrap_err().to_string(), "API error (RESOURCE_EXHAUSTED): Quota exceeded");
+
+        let blocked = json!({"promptFeedback": {"blockReason": "SAFETY"}});
+        assert_eq!(extract_text(&blocked).unwrap_err().to_string(), "Prompt blocked by Gemini: SAFETY");
+
+        let stopped = json!({"candidates": [{"content": {"parts": [{"text": "Hi"}]}, "finishReason": "SAFETY"}]});
+        assert_eq!(extract_text(&stopped).unwrap_err().to_string(), "Response blocked by Gemini: SAFETY");
+
+        let finished = json!({"candidates": [{"content": {"parts": [{"text": "Hi"}]}, "finishReason": "STOP"}]});
+        assert_eq!(extract_text(&finished).unwrap(), "Hi");
+    }
+
+    #[tokio::test]
+    async fn streams_parts_from_endpoint() {
+        let first = event(json!({"candidates": [{"content": {"parts": [{"text": "こん"}, {"text": "に"}]}}]}));
+        let second = event(json!({"candidates": [{"content": {"parts": [{"text": "ちは"}]}, "finishReason": "STOP"}]}));
+        let (middle, rest) = second.split_at(20);
+        let (results, request) = stream_result(&[&first, middle, rest]).await;
+
+        let chunks: Vec<String> = results.into_iter().map(Result::unwrap).collect();
+        assert_eq!(chunks.concat(), "こんにちは");
+        assert!(request.starts_with("POST /models/gemini-test:streamGenerateContent?alt=sse "));
+        assert!(request.to_ascii_lowercase().contains("x-goog-api-key: key-test"));
+    }
+
+    #[tokio::test]
+    async fn stream_stops_on_safety_finish() {
+        let first = event(json!({"candidates": [{"content": {"parts": [{"text": "Part"}]}}]}));
+        let second = event(json!({"candidates": [{"content": {"parts": []}, "finishReason": "SAFETY"}]}));
+        let (results, _) = stream_result(&[&first, &second]).await;
+
+        assert_eq!(results[0].as_ref().unwrap(), "Part");
+        assert_eq!(results[1].as_ref().unwrap_err().to_string(), "Response blocked by Gemini: SAFETY");
+    }
+
+    #[tokio::test]
+    async fn stream_reports_blocked_prompt_and_error_objects() {
+        let blocked = event(json!({"promptFeedback": {"blockReason": "PROHIBITED_CONTENT"}}));
+        let (results, _) = stream_result(&[&blocked]).await;
+        assert_eq!(results[0].as_ref().unwrap_err().to_string(), "Prompt blocked by Gemini: PROHIBITED_CONTENT");
+
+        let error = event(json!({"error": {"status": "INTERNAL", "message": "Backend error"}}));
+        let (results, _) = stream_result(&[&error]).await;
+        assert_eq!(results[0].as_ref().unwrap_err().to_string(), "API error (INTERNAL): Backend error");
+    }
+
+    #[tokio::test]
+    async fn empty_stream_is_an_error() {
+        let usage = event(json!({"usageMetadata": {"promptTokenCount": 3}}));
+        let (results, _) = stream_result(&[&usage]).await;
+        let errors: Vec<String> = results.into_iter().filter_map(|r| r.err()).map(|e| e.to_string()).collect();
+        assert_eq!(errors, vec!["No candidates in response"]);
+    }
+
+    #[tokio::test]
+    async fn translates_without_streaming() {
+        let body = json!({"candidates": [{"content": {"parts": [{"text": " Hallo "}, {"text": "Welt "}]}}]}).to_string();
+        let (api_base, server) = serve_once("200 OK", "application/json", &body).await;
+
+        assert_eq!(provider(api_base).translate("Hello world", &languages()).await.unwrap(), "Hallo Welt");
+        assert!(server.await.unwrap().starts_with("POST /models/gemini-test:generateContent "));
+    }
+
+    #[tokio::test]
+    async fn blocked_prompt_fails_without_streaming() {
+        let body = json!({"promptFeedback": {"blockReason": "SAFETY"}}).to_string();
+        let (api_base, _server) = serve_once("200 OK", "application/json", &body).await;
+
+        let error = provider(api_base).translate("Hello", &languages()).await.unwrap_err();
+        assert_eq!(error.to_string(), "Prompt blocked by Gemini: SAFETY");
+    }
+}
@@ -0,0 +1,176 @@
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// 语言代码（`auto` 表示自动检测）
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    Auto,
+    Code(String),
+}
+
+impl Language {
+    pub fn is_auto(&self) -> bool {
+        matches!(self, Self::Auto)
+    }
+
+    pub fn code(&self) -> &str {
+        match self {
+            Self::Auto => "auto",
+            Self::Code(code) => code,
+        }
+    }
+
+    /// 是否为英文（en, en-US, en-GB ...）
+    pub fn is_english(&self) -> bool {
+        let code = self.code().to_ascii_lowercase();
+        code == "en" || code.starts_with("en-") || code.starts_with("en_")
+    }
+
+    /// 用于prompt的语言名称，未知代码原样返回
+    pub fn display_name(&self) -> String {
+        let name = match self.code().to_ascii_lowercase().replace('_', "-").as_str() {
+            "auto" => "the detected language",
+            "zh" | "zh-cn" | "zh-hans" => "Simplified Chinese (简体中文)",
+            "zh-tw" | "zh-hk" | "zh-hant" => "Traditional Chinese (繁體中文)",
+            "en" | "en-us" | "en-gb" => "English",
+            "ja" => "Japanese (日本語)",
+            "ko" => "Korean (한국어)",
+            "es" => "Spanish (Español)",
+            "fr" => "French (Français)",
+            "de" => "German (Deutsch)",
+            "it" => "Italian (Italiano)",
+            "pt" | "pt-br" | "pt-pt" => "Portuguese (Português)",
+            "ru" => "Russian (Русский)",
+            _ => return self.code().to_string(),
+        };
+        name.to_string()
+    }
+}
+
+impl FromStr for Language {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(anyhow!("Language code must not be empty"));
+        }
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        if !s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(anyhow!("Invalid language code: {}", s));
+        }
+        Ok(Self::Code(s.to_string()))
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// 中英互译时粗略判断原文是否像英文（有字母且全为ASCII字母），
+/// 供按请求计费的翻译API先猜目标语言，猜错时才需要第二次请求
+pub fn looks_english(text: &str) -> bool {
+    let mut letters = text.chars().filter(|c| c.is_alphabetic()).peekable();
+    letters.peek().is_some() && letters.all(|c| c.is_ascii_alphabetic())
+}
+
+/// 源语言 → 目标语言
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LanguagePair {
+    pub source: Language,
+    pub target: Language,
+}
+
+impl LanguagePair {
+    pub fn new(source: Language, target: Language) -> Self {
+        Self { source, target }
+    }
+
+    /// 源语言已知而目标为auto时，按中英互译规则确定目标语言
+    pub fn resolved_target(&self) -> Language {
+        match (&self.source, &self.target) {
+            (Language::Auto, Language::Auto) => Language::Auto,
+            (source, Language::Auto) if source.is_english() => Language::Code("zh-CN".to_string()),
+            (_, Language::Auto) => Language::Code("en".to_string()),
+            (_, target) => target.clone(),
+        }
+    }
+}
+
+impl fmt::Display for LanguagePair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} → {}", self.source, self.target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_english_from_the_script() {
+        assert!(looks_english("Hello, world! 42"));
+        assert!(looks_english("Bonjour le monde"));
+        assert!(!looks_english("你好 world"));
+        assert!(!looks_english("Café"));
+        assert!(!looks_english("123 !?"));
+    }
+
+    fn code(code: &str) -> Language {
+        Language::Code(code.to_string())
+    }
+
+    #[test]
+    fn parses_codes() {
+        assert_eq!("auto".parse::<Language>().unwrap(), Language::Auto);
+        assert_eq!(" AUTO ".parse::<Language>().unwrap(), Language::Auto);
+        assert_eq!("ja".parse::<Language>().unwrap(), code("ja"));
+        assert_eq!("zh_TW".parse::<Language>().unwrap(), code("zh_TW"));
+        assert_eq!(" pt-BR\n".parse::<Language>().unwrap(), code("pt-BR"));
+
+        assert_eq!("".parse::<Language>().unwrap_err().to_string(), "Language code must not be empty");
+        assert_eq!("en us".parse::<Language>().unwrap_err().to_string(), "Invalid language code: en us");
+        assert!("日本語".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn names_known_codes() {
+        assert_eq!(code("zh-CN").display_name(), "Simplified Chinese (简体中文)");
+        assert_eq!(code("zh_hant").display_name(), "Traditional Chinese (繁體中文)");
+        assert_eq!(code("EN-gb").display_name(), "English");
+        assert_eq!(code("ja").display_name(), "Japanese (日本語)");
+        // 未知代码原样交给模型
+        assert_eq!(code("sw").display_name(), "sw");
+        assert_eq!(Language::Auto.display_name(), "the detected language");
+
+        assert!(code("en_US").is_english());
+        assert!(!code("eo").is_english());
+    }
+
+    #[test]
+    fn resolves_auto_targets() {
+        let pair = |source: Language, target: Language| LanguagePair::new(source, target).resolved_target();
+
+        // 源和目标都是auto：保持中英互译，由模型判断
+        assert_eq!(pair(Language::Auto, Language::Auto), Language::Auto);
+        // 只指定源语言：英文译为中文，其他译为英文
+        assert_eq!(pair(code("en-GB"), Language::Auto), code("zh-CN"));
+        assert_eq!(pair(code("zh"), Language::Auto), code("en"));
+        assert_eq!(pair(code("fr"), Language::Auto), code("en"));
+        // 显式目标语言总是优先
+        assert_eq!(pair(Language::Auto, code("ja")), code("ja"));
+        assert_eq!(pair(code("en"), code("de")), code("de"));
+    }
+
+    #[test]
+    fn displays_pairs() {
+        assert_eq!(LanguagePair::default().to_string(), "auto → auto");
+        assert_eq!(LanguagePair::new(code("en"), code("ja")).to_string(), "en → ja");
+    }
+}
@@ -0,0 +1,260 @@
+use super::{
+    language::looks_english, single_response, Language, LanguagePair, ProviderError, StreamingResponse,
+    TranslationProvider,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+struct TranslateRequest<'a> {
+    q: &'a str,
+    source: String,
+    target: String,
+    format: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+    #[serde(rename = "detectedLanguage")]
+    detected_language: Option<DetectedLanguage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DetectedLanguage {
+    language: String,
+}
+
+/// 自托管LibreTranslate（整段返回，不支持流式）
+pub struct LibreTranslateProvider {
+    client: Client,
+    api_key: String,
+    api_base: String,
+    verbose: bool,
+}
+
+impl LibreTranslateProvider {
+    pub fn new(api_key: String, api_base: String, verbose: bool) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+            api_base,
+            verbose,
+        }
+    }
+
+    fn verbose_log(&self, msg: impl AsRef<str>) {
+        if self.verbose {
+            eprintln!("[VERBOSE] {}", msg.as_ref());
+        }
+    }
+
+    async fn request(&self, text: &str, source: &Language, target: &Language) -> Result<TranslateResponse> {
+        let request = TranslateRequest {
+            q: text,
+            source: language_code(source),
+            target: language_code(target),
+            format: "text",
+            api_key: (!self.api_key.is_empty()).then_some(self.api_key.as_str()),
+        };
+
+        let url = format!("{}/translate", self.api_base.trim_end_matches('/'));
+        self.verbose_log(format!("API URL: {}", url));
+        self.verbose_log(format!(
+            "LibreTranslate languages: {} → {}",
+            request.source, request.target
+        ));
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send()
+            .await?;
+
+        let status = response.status();
+        self.verbose_log(format!("HTTP Status: {}", status));
+
+        if !status.is_success() {
+            let error = ProviderError::from_response(response).await;
+            self.verbose_log(format!("Error response body: {}", error.body()));
+            return Err(error.into());
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to parse response JSON: {}", e))
+    }
+}
+
+/// LibreTranslate语言代码（繁体中文为 "zt"，其余取主语言）
+fn language_code(language: &Language) -> String {
+    match language.code().to_ascii_lowercase().replace('_', "-").as_str() {
+        "zh-tw" | "zh-hk" | "zh-hant" => "zt".to_string(),
+        code => code.split('-').next().unwrap_or_default().to_string(),
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for LibreTranslateProvider {
+    async fn translate_stream(&self, text: &str, languages: &LanguagePair) -> Result<StreamingResponse> {
+        Ok(single_response(self.translate(text, languages).await?))
+    }
+
+    async fn translate(&self, text: &str, languages: &LanguagePair) -> Result<String> {
+        self.verbose_log(format!("Translating text: {}", text));
+        self.verbose_log(format!("Languages: {}", languages));
+
+        let target = languages.resolved_target();
+        let translation = if target.is_auto() {
+            // 中英互译：按文字猜测原文是否为英文，检测结果与猜测不符时才重新请求
+            let english = Language::Code("en".to_string());
+            let chinese = Language::Code("zh-CN".to_string());
+            let guessed_english = looks_english(text);
+            let guess = if guessed_english { &chinese } else { &english };
+            let first = self.request(text, &languages.source, guess).await?;
+            let detected_english = first
+                .detected_language
+                .as_ref()
+                .map_or(guessed_english, |detected| detected.language.eq_ignore_ascii_case("en"));
+            if detected_english == guessed_english {
+                first.translated_text
+            } else if detected_english {
+                self.request(text, &english, &chinese).await?.translated_text
+            } else {
+                self.request(text, &languages.source, &english).await?.translated_text
+            }
+        } else {
+            self.request(text, &languages.source, &target).await?.translated_text
+        };
+
+        self.verbose_log(format!("Translation result: {}", translation));
+        Ok(translation.trim().to_string())
+    }
+
+    fn name(&self) -> &str {
+        "LibreTranslate"
+    }
+
+    fn model(&self) -> &str {
+        "default"
+    }
+
+    fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    fn supports_placeholders(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock::{serve, Reply};
+
+    fn code(code: &str) -> Language {
+        code.parse().unwrap()
+    }
+
+    fn reply(detected: Option<&str>, text: &str) -> Reply {
+        let detected = detected.map_or(String::new(), |language| {
+            format!(r#","detectedLanguage":{{"confidence":90,"language":"{}"}}"#, language)
+        });
+        let body = format!(r#"{{"translatedText":"{}"{}}}"#, text, detected);
+        Reply::new("200 OK", "application/json", &body)
+    }
+
+    /// 返回译文与各次请求的 (source, target)
+    async fn translate(
+        api_key: &str,
+        text: &str,
+        languages: &LanguagePair,
+        replies: Vec<Reply>,
+    ) -> (String, Vec<(String, String)>) {
+        let (api_base, server) = serve(replies).await;
+        let provider = LibreTranslateProvider::new(api_key.to_string(), api_base, false);
+        let translation = provider.translate(text, languages).await.unwrap();
+        let field = |request: &str, name: &str| {
+            request.split(&format!(r#""{}":""#, name)).nth(1).unwrap().split('"').next().unwrap().to_string()
+        };
+        let requests = server
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| (field(request, "source"), field(request, "target")))
+            .collect();
+        (translation, requests)
+    }
+
+    #[test]
+    fn maps_language_codes() {
+        assert_eq!(language_code(&Language::Auto), "auto");
+        assert_eq!(language_code(&code("zh-CN")), "zh");
+        assert_eq!(language_code(&code("zh_TW")), "zt");
+        assert_eq!(language_code(&code("zh-Hant")), "zt");
+        assert_eq!(language_code(&code("en-GB")), "en");
+        assert_eq!(language_code(&code("PT-br")), "pt");
+    }
+
+    #[tokio::test]
+    async fn sends_explicit_languages_and_api_key() {
+        let (api_base, server) = serve(vec![reply(None, " Hallo ")]).await;
+        let provider = LibreTranslateProvider::new("secret".to_string(), api_base, false);
+        let languages = LanguagePair::new(code("en"), code("de"));
+
+        assert_eq!(provider.translate("Hello", &languages).await.unwrap(), "Hallo");
+        let request = server.await.unwrap().remove(0);
+        assert!(request.starts_with("POST /translate "));
+        assert!(request.contains(r#""q":"Hello","source":"en","target":"de","format":"text","api_key":"secret""#));
+    }
+
+    #[tokio::test]
+    async fn omits_an_empty_api_key() {
+        let languages = LanguagePair::new(code("en"), code("de"));
+        let (api_base, server) = serve(vec![reply(None, "Hallo")]).await;
+        LibreTranslateProvider::new(String::new(), api_base, false).translate("Hello", &languages).await.unwrap();
+        assert!(!server.await.unwrap()[0].contains("api_key"));
+    }
+
+    #[tokio::test]
+    async fn auto_translates_in_one_request_when_the_guess_holds() {
+        let auto = LanguagePair::default();
+        let (translation, requests) = translate("", "Hello", &auto, vec![reply(Some("en"), "你好")]).await;
+        assert_eq!(translation, "你好");
+        assert_eq!(requests, vec![("auto".to_string(), "zh".to_string())]);
+
+        let (translation, requests) = translate("", "你好", &auto, vec![reply(Some("zh"), "Hello")]).await;
+        assert_eq!(translation, "Hello");
+        assert_eq!(requests, vec![("auto".to_string(), "en".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn auto_retries_when_detection_contradicts_the_guess() {
+        let auto = LanguagePair::default();
+        let replies = vec![reply(Some("fr"), "你好世界"), reply(Some("fr"), "Hello world")];
+        let (translation, requests) = translate("", "Bonjour le monde", &auto, replies).await;
+        assert_eq!(translation, "Hello world");
+        assert_eq!(
+            requests,
+            vec![("auto".to_string(), "zh".to_string()), ("auto".to_string(), "en".to_string())]
+        );
+
+        let replies = vec![reply(Some("en"), "Café time"), reply(None, "咖啡时间")];
+        let (translation, requests) = translate("", "Café time", &auto, replies).await;
+        assert_eq!(translation, "咖啡时间");
+        assert_eq!(requests, vec![("auto".to_string(), "en".to_string()), ("en".to_string(), "zh".to_string())]);
+    }
+}
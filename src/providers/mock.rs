@@ -0,0 +1,88 @@
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// 测试用HTTP应答；body可分成多段写出，模拟跨网络包到达的数据
+pub struct Reply {
+    status: String,
+    content_type: String,
+    parts: Vec<String>,
+}
+
+impl Reply {
+    pub fn new(status: &str, content_type: &str, body: &str) -> Self {
+        Self::split(status, content_type, &[body])
+    }
+
+    pub fn split(status: &str, content_type: &str, parts: &[&str]) -> Self {
+        Self {
+            status: status.to_string(),
+            content_type: content_type.to_string(),
+            parts: parts.iter().map(|part| part.to_string()).collect(),
+        }
+    }
+}
+
+/// 本地HTTP服务：每个连接按顺序应答一个Reply，返回收到的各个请求
+pub async fn serve(replies: Vec<Reply>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let handle = tokio::spawn(async move {
+        let mut requests = Vec::new();
+        for reply in replies {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            requests.push(read_request(&mut socket).await);
+
+            let length: usize = reply.parts.iter().map(String::len).sum();
+            let head = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                reply.status, reply.content_type, length
+            );
+            socket.write_all(head.as_bytes()).await.unwrap();
+            for (index, part) in reply.parts.iter().enumerate() {
+                if index > 0 {
+                    tokio::time::sleep(Duration::from_millis(30)).await;
+                }
+                socket.write_all(part.as_bytes()).await.unwrap();
+                socket.flush().await.unwrap();
+            }
+            socket.shutdown().await.ok();
+        }
+        requests
+    });
+
+    (format!("http://{}", addr), handle)
+}
+
+/// 只应答一次的本地HTTP服务，返回收到的请求
+pub async fn serve_once(status: &str, content_type: &str, body: &str) -> (String, JoinHandle<String>) {
+    let (api_base, handle) = serve(vec![Reply::new(status, content_type, body)]).await;
+    (api_base, tokio::spawn(async move { handle.await.unwrap().remove(0) }))
+}
+
+/// 读完请求头与Content-Length长度的请求体
+async fn read_request(socket: &mut TcpStream) -> String {
+    let mut request = Vec::new();
+    let mut buffer = [0; 4096];
+    loop {
+        let n = socket.read(&mut buffer).await.unwrap();
+        request.extend_from_slice(&buffer[..n]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some(head_end) = text.find("\r\n\r\n") {
+            let length = text[..head_end]
+                .to_ascii_lowercase()
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:")?.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            if request.len() >= head_end + 4 + length {
+                break;
+            }
+        }
+        if n == 0 {
+            break;
+        }
+    }
+    String::from_utf8_lossy(&request).into_owned()
+}
@@ -1,20 +1,126 @@
+pub mod anthropic;
+pub mod deepl;
+pub mod error;
+pub mod fallback;
+#[cfg(test)]
+pub mod fake;
+pub mod gemini;
+pub mod language;
+pub mod libretranslate;
+#[cfg(test)]
+pub mod mock;
+pub mod ollama;
 pub mod openai;
+pub mod prompt;
+pub mod registry;
+pub mod retry;
+
+pub use error::ProviderError;
+pub use language::{Language, LanguagePair};
+pub use registry::create_chain;
 
 use anyhow::Result;
 use async_trait::async_trait;
-use futures::Stream;
+use futures::{stream, Stream, StreamExt};
 use std::pin::Pin;
+use std::sync::Arc;
 
 pub type StreamingResponse = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
 
+/// 标注了应答Provider的增量流：(更换Provider时为应答者，否则为None, 增量)
+pub type NamedStream = Pin<Box<dyn Stream<Item = Result<(Option<Responder>, String)>> + Send>>;
+
+/// 实际应答的Provider（fallback链中可能不是首个）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Responder {
+    pub name: String,
+    pub model: String,
+}
+
+impl Responder {
+    pub fn of<P: TranslationProvider + ?Sized>(provider: &P) -> Self {
+        Self {
+            name: provider.name().to_string(),
+            model: provider.model().to_string(),
+        }
+    }
+}
+
+/// 后台事件通知回调（如重试、切换fallback），TUI中显示为通知
+pub type Notifier = Arc<dyn Fn(String) + Send + Sync>;
+
 #[async_trait]
 pub trait TranslationProvider: Send + Sync {
     /// 流式翻译（用于TUI模式）
-    async fn translate_stream(&self, text: &str) -> Result<StreamingResponse>;
+    async fn translate_stream(&self, text: &str, languages: &LanguagePair) -> Result<StreamingResponse>;
+
+    /// 流式翻译，同时返回实际应答的Provider
+    async fn translate_stream_named(
+        &self,
+        text: &str,
+        languages: &LanguagePair,
+    ) -> Result<(Responder, StreamingResponse)> {
+        let stream = self.translate_stream(text, languages).await?;
+        Ok((Responder::of(self), stream))
+    }
 
     /// 同步翻译（用于快速模式）
-    async fn translate(&self, text: &str) -> Result<String>;
+    async fn translate(&self, text: &str, languages: &LanguagePair) -> Result<String>;
+
+    /// 附带前文的同步翻译（用于长文档分块）；不支持上下文的Provider忽略context
+    async fn translate_with_context(&self, text: &str, languages: &LanguagePair, context: &str) -> Result<String> {
+        let _ = context;
+        self.translate(text, languages).await
+    }
+
+    /// 附带前文的同步翻译，同时返回实际应答的Provider
+    async fn translate_named(
+        &self,
+        text: &str,
+        languages: &LanguagePair,
+        context: &str,
+    ) -> Result<(Responder, String)> {
+        let translation = self.translate_with_context(text, languages, context).await?;
+        Ok((Responder::of(self), translation))
+    }
 
     /// Provider名称
     fn name(&self) -> &str;
+
+    /// 使用的模型
+    fn model(&self) -> &str;
+
+    /// API地址
+    fn api_base(&self) -> &str;
+
+    /// API密钥（仅用于verbose日志，输出前需脱敏）
+    fn api_key(&self) -> &str;
+
+    /// 能否按提示保留 ⟦N⟧ 占位符（Markdown模式依赖此能力；非LLM的翻译API不支持）
+    fn supports_placeholders(&self) -> bool {
+        true
+    }
+}
+
+/// 将完整翻译结果包装为单项流（用于不支持流式的Provider）
+pub fn single_response(translation: String) -> StreamingResponse {
+    Box::pin(stream::once(async move { Ok(translation) }))
+}
+
+/// 单个Provider的流：应答者随第一段增量给出
+pub fn named(responder: Responder, stream: StreamingResponse) -> NamedStream {
+    let mut responder = Some(responder);
+    Box::pin(stream.map(move |result| result.map(|delta| (responder.take(), delta))))
+}
+
+/// 等待第一段非空内容；在此之前出错则返回该错误（此时可安全重试或切换Provider）
+pub async fn first_chunk(mut stream: StreamingResponse) -> Result<StreamingResponse> {
+    loop {
+        match stream.next().await {
+            Some(Ok(delta)) if delta.is_empty() => continue,
+            Some(Err(error)) => return Err(error),
+            Some(first) => return Ok(Box::pin(stream::once(async { first }).chain(stream))),
+            None => return Ok(Box::pin(stream::empty())),
+        }
+    }
 }
@@ -0,0 +1,280 @@
+use super::{
+    prompt::create_prompt_with_context, LanguagePair, ProviderError, StreamingResponse, TranslationProvider,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Serialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    stream: bool,
+}
+
+/// 本地Ollama（/api/chat，NDJSON流式协议，无需API密钥）
+pub struct OllamaProvider {
+    client: Client,
+    api_key: String,
+    api_base: String,
+    model: String,
+    verbose: bool,
+}
+
+impl OllamaProvider {
+    pub fn new(api_key: String, api_base: String, model: String, verbose: bool) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+            api_base,
+            model,
+            verbose,
+        }
+    }
+
+    fn verbose_log(&self, msg: impl AsRef<str>) {
+        if self.verbose {
+            eprintln!("[VERBOSE] {}", msg.as_ref());
+        }
+    }
+
+    fn build_request(&self, text: &str, languages: &LanguagePair, context: &str, stream: bool) -> ChatRequest {
+        ChatRequest {
+            model: self.model.clone(),
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: create_prompt_with_context(text, languages, context),
+            }],
+            stream,
+        }
+    }
+
+    async fn send(&self, request: &ChatRequest) -> Result<reqwest::Response> {
+        let url = format!("{}/api/chat", self.api_base.trim_end_matches('/'));
+        self.verbose_log(format!("API URL: {}", url));
+
+        let mut builder = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json");
+        if !self.api_key.is_empty() {
+            builder = builder.header("Authorization", format!("Bearer {}", self.api_key));
+        }
+
+        let response = builder.json(request).send().await?;
+
+        let status = response.status();
+        self.verbose_log(format!("HTTP Status: {}", status));
+
+        if !status.is_success() {
+            let error = ProviderError::from_response(response).await;
+            self.verbose_log(format!("Error response body: {}", error.body()));
+            return Err(error.into());
+        }
+
+        Ok(response)
+    }
+}
+
+/// 解析一行NDJSON，返回 message.content
+fn parse_chunk(line: &[u8]) -> Result<String> {
+    let value: Value =
+        serde_json::from_slice(line).map_err(|e| anyhow!("Failed to parse NDJSON chunk: {}", e))?;
+
+    if let Some(error) = value["error"].as_str() {
+        return Err(anyhow!("Ollama error: {}", error));
+    }
+
+    Ok(value["message"]["content"].as_str().unwrap_or("").to_string())
+}
+
+#[async_trait]
+impl TranslationProvider for OllamaProvider {
+    async fn translate_stream(&self, text: &str, languages: &LanguagePair) -> Result<StreamingResponse> {
+        let request = self.build_request(text, languages, "", true);
+        let response = self.send(&request).await?;
+
+        // 按换行切分字节流，每行一个JSON对象
+        let bytes = response.bytes_stream();
+        let lines = stream::unfold(
+            (bytes, Vec::<u8>::new(), false),
+            |(mut bytes, mut buffer, mut finished)| async move {
+                loop {
+                    if let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+                        let line: Vec<u8> = buffer.drain(..=pos).collect();
+                        return Some((Ok(line), (bytes, buffer, finished)));
+                    }
+                    if finished {
+                        if buffer.is_empty() {
+                            return None;
+                        }
+                        let line = std::mem::take(&mut buffer);
+                        return Some((Ok(line), (bytes, buffer, finished)));
+                    }
+                    match bytes.next().await {
+                        Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                        Some(Err(e)) => {
+                            finished = true;
+                            buffer.clear();
+                            return Some((
+                                Err(ProviderError::Stream(e.to_string()).into()),
+                                (bytes, buffer, finished),
+                            ));
+                        }
+                        None => finished = true,
+                    }
+                }
+            },
+        );
+
+        let stream = lines.filter_map(|line| async move {
+            match line {
+                Ok(line) if line.iter().all(u8::is_ascii_whitespace) => None,
+                Ok(line) => Some(parse_chunk(&line)),
+                Err(e) => Some(Err(e)),
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+
+    async fn translate(&self, text: &str, languages: &LanguagePair) -> Result<String> {
+        self.translate_with_context(text, languages, "").await
+    }
+
+    async fn translate_with_context(&self, text: &str, languages: &LanguagePair, context: &str) -> Result<String> {
+        self.verbose_log(format!("Translating text: {}", text));
+        self.verbose_log(format!("Languages: {}", languages));
+        self.verbose_log(format!("API Model: {}", self.model));
+
+        let request = self.build_request(text, languages, context, false);
+        let response = self.send(&request).await?;
+        let response_text = response.text().await?;
+        self.verbose_log(format!("Response body length: {} bytes", response_text.len()));
+
+        let translation = parse_chunk(response_text.as_bytes())?;
+        if translation.trim().is_empty() {
+            return Err(anyhow!("No message content in response"));
+        }
+
+        self.verbose_log(format!("Translation result: {}", translation));
+        Ok(translation.trim().to_string())
+    }
+
+    fn name(&self) -> &str {
+        "Ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    fn api_key(&self) -> &str {
+        &self.api_key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Language;
+    use super::*;
+    use crate::providers::mock::{serve, serve_once, Reply};
+
+    fn provider(api_base: String) -> OllamaProvider {
+        OllamaProvider::new(String::new(), api_base, "llama-test".to_string(), false)
+    }
+
+    fn languages() -> LanguagePair {
+        LanguagePair::new(Language::Auto, "ja".parse().unwrap())
+    }
+
+    async fn collect(stream: StreamingResponse) -> Vec<Result<String>> {
+        stream.collect().await
+    }
+
+    #[test]
+    fn parses_message_content() {
+        let line = br#"{"model":"m","message":{"role":"assistant","content":"Hi"},"done":false}"#;
+        assert_eq!(parse_chunk(line).unwrap(), "Hi");
+        assert_eq!(parse_chunk(br#"{"done":true}"#).unwrap(), "");
+        assert_eq!(parse_chunk(br#"{"error":"model not found"}"#).unwrap_err().to_string(), "Ollama error: model not found");
+        assert!(parse_chunk(b"{").unwrap_err().to_string().starts_with("Failed to parse NDJSON chunk"));
+    }
+
+    #[tokio::test]
+    async fn joins_lines_split_across_network_chunks() {
+        let reply = Reply::split(
+            "200 OK",
+            "application/x-ndjson",
+            &[
+                "{\"message\":{\"content\":\"こんに\"}}\n{\"message\":{\"con",
+                "tent\":\"ちは\"}}\n\n{\"message\":{\"content\":\"\"},\"done\":true}",
+            ],
+        );
+        let (api_base, server) = serve(vec![reply]).await;
+
+        let stream = provider(api_base).translate_stream("Hello", &languages()).await.unwrap();
+        let chunks: Vec<String> = collect(stream).await.into_iter().map(Result::unwrap).collect();
+        // 最后一行没有换行符也要解析
+        assert_eq!(chunks, vec!["こんに", "ちは", ""]);
+
+        let request = server.await.unwrap().remove(0);
+        assert!(request.starts_with("POST /api/chat "));
+        assert!(request.contains(r#""stream":true"#));
+        assert!(!request.to_ascii_lowercase().contains("authorization:"));
+    }
+
+    #[tokio::test]
+    async fn reports_error_lines_in_the_stream() {
+        let body = "{\"message\":{\"content\":\"Hi\"}}\n{\"error\":\"out of memory\"}\n";
+        let (api_base, _server) = serve_once("200 OK", "application/x-ndjson", body).await;
+
+        let stream = provider(api_base).translate_stream("Hello", &languages()).await.unwrap();
+        let chunks = collect(stream).await;
+        assert_eq!(chunks[0].as_ref().unwrap(), "Hi");
+        assert_eq!(chunks[1].as_ref().unwrap_err().to_string(), "Ollama error: out of memory");
+    }
+
+    #[tokio::test]
+    async fn translates_without_streaming() {
+        let body = r#"{"message":{"role":"assistant","content":" こんにちは \n"},"done":true}"#;
+        let (api_base, server) = serve_once("200 OK", "application/json", body).await;
+
+        assert_eq!(provider(api_base).translate("Hello", &languages()).await.unwrap(), "こんにちは");
+        assert!(server.await.unwrap().contains(r#""stream":false"#));
+    }
+
+    #[tokio::test]
+    async fn surfaces_error_bodies() {
+        let (api_base, _server) = serve_once("404 Not Found", "application/json", r#"{"error":"model 'x' not found"}"#).await;
+        let error = provider(api_base).translate("Hello", &languages()).await.unwrap_err();
+        assert_eq!(error.downcast_ref::<ProviderError>().unwrap().status(), Some(404));
+        assert!(error.to_string().contains("model 'x' not found"));
+
+        let (api_base, _server) = serve_once("200 OK", "application/json", r#"{"error":"model is loading"}"#).await;
+        let error = provider(api_base).translate("Hello", &languages()).await.unwrap_err();
+        assert_eq!(error.to_string(), "Ollama error: model is loading");
+    }
+
+    #[tokio::test]
+    async fn empty_translation_is_an_error() {
+        let body = r#"{"message":{"role":"assistant","content":"  "},"done":true}"#;
+        let (api_base, _server) = serve_once("200 OK", "application/json", body).await;
+
+        let error = provider(api_base).translate("Hello", &languages()).await.unwrap_err();
+        assert_eq!(error.to_string(), "No message content in response");
+    }
+}
@@ -1,4 +1,7 @@
-use super::{StreamingResponse, TranslationProvider};
+use super::{
+    prompt::{create_prompt, create_prompt_with_context}, LanguagePair, ProviderError, StreamingResponse, TranslationProvider,
+};
+use crate::utils::mask_sensitive;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use eventsource_stream::Eventsource;
@@ -40,97 +43,35 @@ pub struct OpenAIProvider {
     api_key: String,
     api_base: String,
     model: String,
-    target_language: String,
     verbose: bool,
 }
 
 impl OpenAIProvider {
-    pub fn new(api_key: String, api_base: String, model: String, target_language: String, verbose: bool) -> Self {
+    pub fn new(api_key: String, api_base: String, model: String, verbose: bool) -> Self {
         Self {
             client: Client::new(),
             api_key,
             api_base,
             model,
-            target_language,
             verbose,
         }
     }
 
-    pub fn from_env(verbose: bool) -> Result<Self> {
-        dotenv::dotenv().ok();
-
-        let api_key = std::env::var("OPENAI_API_KEY")
-            .map_err(|_| anyhow!("OPENAI_API_KEY not found in environment"))?;
-        let api_base = std::env::var("OPENAI_API_BASE")
-            .unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
-        let model = std::env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-5-mini".to_string());
-        let target_language =
-            std::env::var("TARGET_LANGUAGE").unwrap_or_else(|_| "zh-CN".to_string());
-
-        Ok(Self::new(api_key, api_base, model, target_language, verbose))
-    }
-
-    // Getter methods for verbose logging
-    pub fn get_api_key(&self) -> &str {
-        &self.api_key
-    }
-
-    pub fn get_api_base(&self) -> &str {
-        &self.api_base
-    }
-
-    pub fn get_model(&self) -> &str {
-        &self.model
-    }
-
-    pub fn get_target_language(&self) -> &str {
-        &self.target_language
-    }
-
     fn verbose_log(&self, msg: impl AsRef<str>) {
         if self.verbose {
             eprintln!("[VERBOSE] {}", msg.as_ref());
         }
     }
-
-    fn mask_sensitive(&self, s: &str, prefix: usize, suffix: usize) -> String {
-        if s.len() <= prefix + suffix {
-            return "*".repeat(s.len());
-        }
-        let visible_len = prefix + suffix;
-        let mask_len = s.len() - visible_len;
-        format!(
-            "{}{}{}",
-            &s[..prefix],
-            "*".repeat(mask_len),
-            &s[s.len() - suffix..]
-        )
-    }
-
-    fn create_prompt(&self, text: &str) -> String {
-        format!(
-            "You are a professional translator. Detect the language of the input text and translate it intelligently:
-- If the input is in Chinese (简体中文/繁体中文), translate to English
-- If the input is in English, translate to Chinese (Simplified Chinese, 简体中文)
-- For other languages, translate to English
-
-Only output the translation result, no explanations or additional text.
-
-Input text:
-{}",
-            text
-        )
-    }
 }
 
 #[async_trait]
 impl TranslationProvider for OpenAIProvider {
-    async fn translate_stream(&self, text: &str) -> Result<StreamingResponse> {
+    async fn translate_stream(&self, text: &str, languages: &LanguagePair) -> Result<StreamingResponse> {
         let request = ChatCompletionRequest {
             model: self.model.clone(),
             messages: vec![ChatMessage {
                 role: "user".to_string(),
-                content: self.create_prompt(text),
+                content: create_prompt(text, languages),
             }],
             stream: true,
         };
@@ -145,9 +86,7 @@ impl TranslationProvider for OpenAIProvider {
             .await?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await?;
-            return Err(anyhow!("API request failed ({}): {}", status, text));
+            return Err(ProviderError::from_response(response).await.into());
         }
 
         let stream = response
@@ -171,17 +110,21 @@ impl TranslationProvider for OpenAIProvider {
                         Err(e) => Err(anyhow!("Failed to parse SSE event: {}", e)),
                     }
                 }
-                Err(e) => Err(anyhow!("Stream error: {}", e)),
+                Err(e) => Err(ProviderError::Stream(e.to_string()).into()),
             });
 
         Ok(Box::pin(stream))
     }
 
-    async fn translate(&self, text: &str) -> Result<String> {
+    async fn translate(&self, text: &str, languages: &LanguagePair) -> Result<String> {
+        self.translate_with_context(text, languages, "").await
+    }
+
+    async fn translate_with_context(&self, text: &str, languages: &LanguagePair, context: &str) -> Result<String> {
         self.verbose_log(format!("Translating text: {}", text));
-        self.verbose_log("Auto-detecting language and translating...");
+        self.verbose_log(format!("Languages: {}", languages));
 
-        let prompt = self.create_prompt(text);
+        let prompt = create_prompt_with_context(text, languages, context);
         self.verbose_log(format!("Generated prompt: {}", prompt));
 
         let request = ChatCompletionRequest {
@@ -196,7 +139,7 @@ impl TranslationProvider for OpenAIProvider {
         let url = format!("{}/chat/completions", self.api_base);
         self.verbose_log(format!("API URL: {}", url));
         self.verbose_log(format!("API Model: {}", self.model));
-        self.verbose_log(format!("API Key: {}", self.mask_sensitive(&self.api_key, 7, 4)));
+        self.verbose_log(format!("API Key: {}", mask_sensitive(&self.api_key, 7, 4)));
 
         self.verbose_log("Sending HTTP request...");
         let response = self
@@ -212,9 +155,9 @@ impl TranslationProvider for OpenAIProvider {
         self.verbose_log(format!("HTTP Status: {}", status));
 
         if !status.is_success() {
-            let error_text = response.text().await?;
-            self.verbose_log(format!("Error response body: {}", error_text));
-            return Err(anyhow!("API request failed ({} {}): {}", status.as_u16(), status.canonical_reason().unwrap_or("Unknown"), error_text));
+            let error = ProviderError::from_response(response).await;
+            self.verbose_log(format!("Error response body: {}", error.body()));
+            return Err(error.into());
         }
 
         let response_text = response.text().await?;
@@ -233,7 +176,7 @@ impl TranslationProvider for OpenAIProvider {
 
         let translation = completion
             .choices
-            .get(0)
+            .first()
             .ok_or_else(|| anyhow!("No choices in response"))?
             .message
             .content
@@ -246,4 +189,16 @@ impl TranslationProvider for OpenAIProvider {
     fn name(&self) -> &str {
         "OpenAI"
     }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    fn api_key(&self) -> &str {
+        &self.api_key
+    }
 }
@@ -0,0 +1,131 @@
+use super::language::LanguagePair;
+
+/// 占位符的起止符号（用于保护Markdown中的代码、链接等）
+pub const PLACEHOLDER_OPEN: char = '⟦';
+pub const PLACEHOLDER_CLOSE: char = '⟧';
+
+/// 第index个占位符，如 ⟦0⟧
+pub fn placeholder(index: usize) -> String {
+    format!("{}{}{}", PLACEHOLDER_OPEN, index, PLACEHOLDER_CLOSE)
+}
+
+/// 根据语言对生成翻译prompt
+pub fn create_prompt(text: &str, languages: &LanguagePair) -> String {
+    create_prompt_with_context(text, languages, "")
+}
+
+/// 长文档分块翻译时附带前文（仅作参考，不翻译）
+pub fn create_prompt_with_context(text: &str, languages: &LanguagePair, context: &str) -> String {
+    let context = if context.trim().is_empty() {
+        String::new()
+    } else {
+        format!(
+            "The input text continues a longer document. The text immediately before it is shown below for consistent terminology and style; do not translate or output it.
+
+Preceding text:
+{}
+
+",
+            context.trim()
+        )
+    };
+    let rules = if text.contains(PLACEHOLDER_OPEN) {
+        format!(
+            "Keep every placeholder such as {} unchanged and at the matching position in the translation.\n",
+            placeholder(0)
+        )
+    } else {
+        String::new()
+    };
+    let target = languages.resolved_target();
+
+    // 源语言与目标语言均为auto：保持中英互译
+    if target.is_auto() {
+        return format!(
+            "You are a professional translator. Detect the language of the input text and translate it intelligently:
+- If the input is in Chinese (简体中文/繁体中文), translate to English
+- If the input is in English, translate to Chinese (Simplified Chinese, 简体中文)
+- For other languages, translate to English
+
+{}Only output the translation result, no explanations or additional text.
+
+{}Input text:
+{}",
+            rules, context, text
+        );
+    }
+
+    let instruction = if languages.source.is_auto() {
+        format!(
+            "Detect the language of the input text and translate it to {}.",
+            target.display_name()
+        )
+    } else {
+        format!(
+            "Translate the input text from {} to {}.",
+            languages.source.display_name(),
+            target.display_name()
+        )
+    };
+
+    format!(
+        "You are a professional translator. {}
+
+{}Only output the translation result, no explanations or additional text.
+
+{}Input text:
+{}",
+        instruction, rules, context, text
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::Language;
+
+    fn pair(source: &str, target: &str) -> LanguagePair {
+        LanguagePair::new(source.parse().unwrap(), target.parse().unwrap())
+    }
+
+    #[test]
+    fn auto_keeps_chinese_english_rules() {
+        let prompt = create_prompt("Hello", &LanguagePair::default());
+        assert!(prompt.contains("If the input is in Chinese (简体中文/繁体中文), translate to English"));
+        assert!(prompt.contains("If the input is in English, translate to Chinese"));
+        assert!(prompt.ends_with("Input text:\nHello"));
+        assert!(!prompt.contains("placeholder"));
+    }
+
+    #[test]
+    fn explicit_target_with_detected_source() {
+        let prompt = create_prompt("Hello", &pair("auto", "ja"));
+        assert!(prompt.starts_with(
+            "You are a professional translator. Detect the language of the input text and translate it to Japanese (日本語)."
+        ));
+        assert!(!prompt.contains("If the input is in Chinese"));
+    }
+
+    #[test]
+    fn explicit_source_and_target() {
+        let prompt = create_prompt("Bonjour", &pair("fr", "de"));
+        assert!(prompt.contains("Translate the input text from French (Français) to German (Deutsch)."));
+
+        // 只指定源语言时按中英互译规则补全目标
+        let prompt = create_prompt("Hello", &LanguagePair::new("en".parse().unwrap(), Language::Auto));
+        assert!(prompt.contains("Translate the input text from English to Simplified Chinese (简体中文)."));
+        let prompt = create_prompt("Hola", &pair("es", "auto"));
+        assert!(prompt.contains("Translate the input text from Spanish (Español) to English."));
+    }
+
+    #[test]
+    fn includes_context_and_placeholder_rules() {
+        let text = format!("Run {} now", placeholder(0));
+        let prompt = create_prompt_with_context(&text, &pair("en", "ja"), "  Earlier text.  ");
+        assert!(prompt.contains("Keep every placeholder such as ⟦0⟧ unchanged"));
+        assert!(prompt.contains("Preceding text:\nEarlier text.\n\nInput text:\nRun ⟦0⟧ now"));
+
+        // 空白上下文不附带
+        assert!(!create_prompt_with_context("Hi", &pair("en", "ja"), " \n ").contains("Preceding text"));
+    }
+}
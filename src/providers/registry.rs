@@ -0,0 +1,391 @@
+use super::{
+    anthropic::AnthropicProvider, deepl::DeepLProvider, fallback::FallbackProvider,
+    gemini::GeminiProvider, libretranslate::LibreTranslateProvider, ollama::OllamaProvider,
+    openai::OpenAIProvider, retry::RetryProvider, Notifier, TranslationProvider,
+};
+use crate::config::{keys::KeySource, settings::ProviderConfig, Config, Profile};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+pub const DEFAULT_PROVIDER: &str = "openai";
+
+/// 已解析的Provider连接参数
+#[derive(Clone, Debug, Default)]
+pub struct ProviderSettings {
+    pub api_key: String,
+    /// api_key为空时，在创建Provider时从此来源读取
+    pub key_source: Option<KeySource>,
+    pub api_base: String,
+    pub model: String,
+}
+
+/// Provider注册信息：名称、环境变量与默认值
+pub struct ProviderSpec {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub description: &'static str,
+    /// API密钥环境变量（按顺序查找）
+    pub key_env: &'static [&'static str],
+    pub key_required: bool,
+    pub base_env: &'static str,
+    pub default_base: &'static str,
+    pub model_env: Option<&'static str>,
+    pub default_model: &'static str,
+    build: fn(ProviderSettings, bool) -> Arc<dyn TranslationProvider>,
+}
+
+pub const PROVIDERS: &[ProviderSpec] = &[
+    ProviderSpec {
+        name: "openai",
+        aliases: &[],
+        description: "OpenAI-compatible chat completions",
+        key_env: &["OPENAI_API_KEY"],
+        key_required: true,
+        base_env: "OPENAI_API_BASE",
+        default_base: "https://api.openai.com/v1",
+        model_env: Some("OPENAI_MODEL"),
+        default_model: "gpt-5-mini",
+        build: |s, verbose| Arc::new(OpenAIProvider::new(s.api_key, s.api_base, s.model, verbose)),
+    },
+    ProviderSpec {
+        name: "anthropic",
+        aliases: &["claude"],
+        description: "Anthropic Messages API",
+        key_env: &["ANTHROPIC_API_KEY"],
+        key_required: true,
+        base_env: "ANTHROPIC_API_BASE",
+        default_base: "https://api.anthropic.com/v1",
+        model_env: Some("ANTHROPIC_MODEL"),
+        default_model: "claude-sonnet-4-5",
+        build: |s, verbose| Arc::new(AnthropicProvider::new(s.api_key, s.api_base, s.model, verbose)),
+    },
+    ProviderSpec {
+        name: "gemini",
+        aliases: &["google"],
+        description: "Google Gemini generateContent",
+        key_env: &["GEMINI_API_KEY", "GOOGLE_API_KEY"],
+        key_required: true,
+        base_env: "GEMINI_API_BASE",
+        default_base: "https://generativelanguage.googleapis.com/v1beta",
+        model_env: Some("GEMINI_MODEL"),
+        default_model: "gemini-2.5-flash",
+        build: |s, verbose| Arc::new(GeminiProvider::new(s.api_key, s.api_base, s.model, verbose)),
+    },
+    ProviderSpec {
+        name: "ollama",
+        aliases: &[],
+        description: "Local Ollama /api/chat (no key needed)",
+        key_env: &["OLLAMA_API_KEY"],
+        key_required: false,
+        base_env: "OLLAMA_HOST",
+        default_base: "http://localhost:11434",
+        model_env: Some("OLLAMA_MODEL"),
+        default_model: "llama3.2",
+        build: |s, verbose| Arc::new(OllamaProvider::new(s.api_key, s.api_base, s.model, verbose)),
+    },
+    ProviderSpec {
+        name: "deepl",
+        aliases: &[],
+        description: "DeepL machine translation",
+        key_env: &["DEEPL_API_KEY"],
+        key_required: true,
+        base_env: "DEEPL_API_BASE",
+        default_base: "https://api.deepl.com/v2",
+        model_env: None,
+        default_model: "default",
+        build: |s, verbose| Arc::new(DeepLProvider::new(s.api_key, s.api_base, verbose)),
+    },
+    ProviderSpec {
+        name: "libretranslate",
+        aliases: &["libre"],
+        description: "Self-hosted LibreTranslate",
+        key_env: &["LIBRETRANSLATE_API_KEY"],
+        key_required: false,
+        base_env: "LIBRETRANSLATE_URL",
+        default_base: "http://localhost:5000",
+        model_env: None,
+        default_model: "default",
+        build: |s, verbose| Arc::new(LibreTranslateProvider::new(s.api_key, s.api_base, verbose)),
+    },
+];
+
+/// 按名称或别名查找Provider（不区分大小写）
+pub fn find(name: &str) -> Option<&'static ProviderSpec> {
+    PROVIDERS.iter().find(|spec| {
+        spec.name.eq_ignore_ascii_case(name)
+            || spec.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+    })
+}
+
+fn env_var(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|value| !value.trim().is_empty())
+}
+
+impl ProviderSpec {
+    /// 解析连接参数：环境变量 > 配置文件 > 默认值
+    ///
+    /// 配置文件中的密钥来源优先于明文 api_key，但不会在此处读取
+    ///
+    /// 配置文件的 [provider] 段只作用于其 `name` 指定的Provider（默认openai）
+    pub fn settings(&self, config: &ProviderConfig) -> ProviderSettings {
+        let configured = config.name.as_deref().unwrap_or(DEFAULT_PROVIDER);
+        let config = match find(configured) {
+            Some(spec) if spec.name == self.name => config.clone(),
+            _ => ProviderConfig::default(),
+        };
+
+        let env_key = self.key_env.iter().find_map(|key| env_var(key));
+        let key_source = env_key.is_none().then(|| config.key_source()).flatten();
+
+        let api_base = env_var(self.base_env)
+            .or(config.api_base)
+            .unwrap_or_else(|| self.default_base.to_string());
+
+        ProviderSettings {
+            api_key: match (env_key, &key_source) {
+                (Some(key), _) => key,
+                (None, Some(_)) => String::new(),
+                (None, None) => config.api_key.unwrap_or_default(),
+            },
+            key_source,
+            // 兼容填写完整endpoint的写法
+            api_base: api_base
+                .trim_end_matches('/')
+                .trim_end_matches("/chat/completions")
+                .to_string(),
+            model: self
+                .model_env
+                .and_then(env_var)
+                .or(config.model)
+                .unwrap_or_else(|| self.default_model.to_string()),
+        }
+    }
+
+    /// 使用给定参数创建Provider
+    pub fn build(&self, mut settings: ProviderSettings, verbose: bool) -> Result<Arc<dyn TranslationProvider>> {
+        if settings.api_key.is_empty() {
+            if let Some(source) = &settings.key_source {
+                settings.api_key = source.load()?;
+            }
+        }
+        if self.key_required && settings.api_key.is_empty() {
+            return Err(anyhow!(
+                "{} not found in environment or config file",
+                self.key_env[0]
+            ));
+        }
+        Ok((self.build)(settings, verbose))
+    }
+}
+
+/// 解析Provider及其连接参数
+///
+/// profile的连接参数仅在其provider与所选Provider一致（或未指定provider）时生效
+pub fn resolve_settings(
+    name: &str,
+    config: &Config,
+    profile: Option<&Profile>,
+) -> Result<(&'static ProviderSpec, ProviderSettings)> {
+    let spec = find(name).ok_or_else(|| anyhow!("Unknown provider: {} (run `ai-tran-cli list`)", name))?;
+    let mut settings = spec.settings(&config.provider);
+
+    if let Some(profile) = profile {
+        let matches = match profile.provider.as_deref() {
+            Some(provider) => find(provider).is_some_and(|p| p.name == spec.name),
+            None => true,
+        };
+        if matches {
+            profile.apply(&mut settings)?;
+        }
+    }
+
+    Ok((spec, settings))
+}
+
+/// 根据名称创建Provider
+pub fn create_provider(
+    name: &str,
+    config: &Config,
+    profile: Option<&Profile>,
+    verbose: bool,
+) -> Result<Arc<dyn TranslationProvider>> {
+    let (spec, settings) = resolve_settings(name, config, profile)?;
+    spec.build(settings, verbose)
+}
+
+/// 按配置项创建Provider：profile名、Provider名或 `provider:model`
+pub fn create_entry(entry: &str, config: &Config, verbose: bool) -> Result<Arc<dyn TranslationProvider>> {
+    if let Ok((_, profile)) = config.profile(entry) {
+        let provider_name = config.provider_name(None, Some(profile));
+        return create_provider(&provider_name, config, Some(profile), verbose);
+    }
+
+    match entry.split_once(':') {
+        Some((name, model)) => {
+            let model_only = Profile {
+                model: Some(model.to_string()),
+                ..Profile::default()
+            };
+            create_provider(name, config, Some(&model_only), verbose)
+        }
+        None => create_provider(entry, config, None, verbose),
+    }
+}
+
+/// 为Provider加上重试，notifier用于在TUI中提示
+fn with_retry(
+    provider: Arc<dyn TranslationProvider>,
+    config: &Config,
+    verbose: bool,
+    notifier: Option<&Notifier>,
+) -> Arc<dyn TranslationProvider> {
+    let mut retry = RetryProvider::new(provider, &config.retry, verbose);
+    if let Some(notifier) = notifier {
+        retry = retry.on_retry(Arc::clone(notifier));
+    }
+    Arc::new(retry)
+}
+
+/// 创建对比模式的Provider（各自带重试，不使用fallback）
+pub fn create_compare(
+    entries: &[String],
+    config: &Config,
+    verbose: bool,
+    notifier: Option<Notifier>,
+) -> Result<Vec<Arc<dyn TranslationProvider>>> {
+    entries
+        .iter()
+        .map(|entry| {
+            let provider = create_entry(entry, config, verbose)
+                .map_err(|e| anyhow!("Compare {}: {}", entry, e))?;
+            Ok(with_retry(provider, config, verbose, notifier.as_ref()))
+        })
+        .collect()
+}
+
+/// 创建完整的Provider：每个Provider各自带重试，外层是fallback链
+///
+/// 重试用尽后才切换到下一个；fallback项可以是profile名或Provider名，无法创建的项被跳过；
+/// notifier用于在TUI中提示重试与切换
+pub fn create_chain(
+    name: &str,
+    config: &Config,
+    profile: Option<&Profile>,
+    verbose: bool,
+    notifier: Option<Notifier>,
+) -> Result<Arc<dyn TranslationProvider>> {
+    let mut providers = vec![create_provider(name, config, profile, verbose)?];
+
+    // 无法创建的fallback项（如缺少密钥）跳过并提示，只有主Provider出错时才失败
+    for entry in config.fallback(profile) {
+        match create_entry(&entry, config, verbose) {
+            Ok(provider) => providers.push(provider),
+            Err(e) => {
+                let message = format!("Fallback {} skipped: {}", entry, e);
+                match &notifier {
+                    Some(notifier) => notifier(message),
+                    None => eprintln!("Warning: {}", message),
+                }
+            }
+        }
+    }
+
+    Ok(chain(providers, config, verbose, notifier))
+}
+
+/// 按顺序组成fallback链，每个Provider各自带重试
+fn chain(
+    providers: Vec<Arc<dyn TranslationProvider>>,
+    config: &Config,
+    verbose: bool,
+    notifier: Option<Notifier>,
+) -> Arc<dyn TranslationProvider> {
+    let mut providers: Vec<_> = providers
+        .into_iter()
+        .map(|provider| with_retry(provider, config, verbose, notifier.as_ref()))
+        .collect();
+
+    if providers.len() == 1 {
+        return providers.remove(0);
+    }
+    let mut fallback = FallbackProvider::new(providers, verbose);
+    if let Some(notifier) = notifier {
+        fallback = fallback.on_fallback(notifier);
+    }
+    Arc::new(fallback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::settings::RetryConfig;
+    use crate::providers::{fake::FakeProvider, LanguagePair};
+    use std::sync::Mutex;
+
+    fn config() -> Config {
+        Config {
+            retry: RetryConfig {
+                max_attempts: 3,
+                base_delay_ms: 1,
+                max_delay_ms: 1,
+            },
+            ..Config::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_each_provider_before_falling_back() {
+        let primary = Arc::new(FakeProvider::new("primary", vec![Err(429), Err(429), Err(429)]));
+        let backup = Arc::new(FakeProvider::new("backup", vec![Err(503), Ok("translated")]));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let notifier: Notifier = {
+            let events = Arc::clone(&events);
+            Arc::new(move |message| events.lock().unwrap().push(message))
+        };
+
+        let provider = chain(
+            vec![primary.clone(), backup.clone()],
+            &config(),
+            false,
+            Some(notifier),
+        );
+        let translation = provider.translate("text", &LanguagePair::default()).await.unwrap();
+
+        assert_eq!(translation, "translated");
+        assert_eq!(primary.calls(), 3);
+        assert_eq!(backup.calls(), 2);
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[2], "primary failed, falling back to backup");
+    }
+
+    #[test]
+    fn skips_fallback_entries_that_cannot_be_built() {
+        let mut config = config();
+        config.provider.fallback = vec!["no-such-provider".to_string(), "ollama".to_string()];
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let notifier: Notifier = {
+            let events = Arc::clone(&events);
+            Arc::new(move |message| events.lock().unwrap().push(message))
+        };
+
+        let provider = create_chain("ollama", &config, None, false, Some(notifier)).unwrap();
+        assert_eq!(provider.name(), "Ollama");
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].starts_with("Fallback no-such-provider skipped: Unknown provider"));
+
+        assert!(create_chain("no-such-provider", &config, None, false, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn falls_back_immediately_on_permanent_errors() {
+        let primary = Arc::new(FakeProvider::new("primary", vec![Err(401)]));
+        let backup = Arc::new(FakeProvider::new("backup", vec![]));
+
+        let provider = chain(vec![primary.clone(), backup.clone()], &config(), false, None);
+        assert_eq!(provider.translate("text", &LanguagePair::default()).await.unwrap(), "text");
+        assert_eq!(primary.calls(), 1);
+        assert_eq!(backup.calls(), 1);
+    }
+}
@@ -0,0 +1,275 @@
+use super::{
+    first_chunk, LanguagePair, Notifier, ProviderError, Responder, StreamingResponse,
+    TranslationProvider,
+};
+use crate::config::settings::RetryConfig;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 超过此值的 Retry-After 不再等待，直接返回错误
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// 为任意Provider加上重试：指数退避（带抖动），429/503时遵循 Retry-After
+pub struct RetryProvider {
+    inner: Arc<dyn TranslationProvider>,
+    config: RetryConfig,
+    verbose: bool,
+    on_retry: Option<Notifier>,
+}
+
+impl RetryProvider {
+    pub fn new(inner: Arc<dyn TranslationProvider>, config: &RetryConfig, verbose: bool) -> Self {
+        Self {
+            inner,
+            config: config.clone(),
+            verbose,
+            on_retry: None,
+        }
+    }
+
+    pub fn on_retry(mut self, callback: Notifier) -> Self {
+        self.on_retry = Some(callback);
+        self
+    }
+
+    fn verbose_log(&self, msg: impl AsRef<str>) {
+        if self.verbose {
+            eprintln!("[VERBOSE] {}", msg.as_ref());
+        }
+    }
+
+    /// 第attempt次失败后是否重试；返回等待时长
+    fn retry_delay(&self, attempt: u32, error: &anyhow::Error) -> Option<Duration> {
+        if attempt >= self.config.max_attempts || !is_retryable(error) {
+            return None;
+        }
+
+        let retry_after = error
+            .downcast_ref::<ProviderError>()
+            .and_then(ProviderError::retry_after);
+        match retry_after {
+            Some(delay) if delay > MAX_RETRY_AFTER => None,
+            Some(delay) => Some(delay),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+
+    /// base * 2^(attempt-1)，上限max_delay，取 [d/2, d] 之间的随机值
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .config
+            .base_delay_ms
+            .saturating_mul(1u64 << (attempt - 1).min(16))
+            .min(self.config.max_delay_ms);
+        let half = exp / 2;
+        Duration::from_millis(half + random() % (exp - half + 1))
+    }
+
+    async fn wait(&self, attempt: u32, delay: Duration, error: &anyhow::Error) {
+        let message = format!(
+            "{}: {}, retry {}/{} in {:.1}s",
+            self.inner.name(),
+            summarize(error),
+            attempt + 1,
+            self.config.max_attempts,
+            delay.as_secs_f32()
+        );
+        self.verbose_log(&message);
+        if let Some(callback) = &self.on_retry {
+            callback(message);
+        }
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// 限流/服务端错误、连接失败与超时可重试
+fn is_retryable(error: &anyhow::Error) -> bool {
+    if let Some(error) = error.downcast_ref::<ProviderError>() {
+        return error.is_retryable();
+    }
+    if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+        return error.is_connect() || error.is_timeout() || error.is_request();
+    }
+    false
+}
+
+/// 通知中显示的简短错误原因
+fn summarize(error: &anyhow::Error) -> String {
+    match error.downcast_ref::<ProviderError>() {
+        Some(ProviderError::Api { status, reason, .. }) => format!("{} {}", status, reason),
+        Some(ProviderError::Stream(_)) => "stream interrupted".to_string(),
+        None if error.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_timeout()) => {
+            "timeout".to_string()
+        }
+        None => "connection error".to_string(),
+    }
+}
+
+fn random() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[async_trait]
+impl TranslationProvider for RetryProvider {
+    async fn translate_stream(&self, text: &str, languages: &LanguagePair) -> Result<StreamingResponse> {
+        Ok(self.translate_stream_named(text, languages).await?.1)
+    }
+
+    async fn translate_stream_named(
+        &self,
+        text: &str,
+        languages: &LanguagePair,
+    ) -> Result<(Responder, StreamingResponse)> {
+        let mut attempt = 1;
+        loop {
+            // 收到第一段内容前的中断可以安全重试
+            let error = match self.inner.translate_stream_named(text, languages).await {
+                Ok((responder, stream)) => match first_chunk(stream).await {
+                    Ok(stream) => return Ok((responder, stream)),
+                    Err(error) => error,
+                },
+                Err(error) => error,
+            };
+
+            match self.retry_delay(attempt, &error) {
+                Some(delay) => self.wait(attempt, delay, &error).await,
+                None => return Err(error),
+            }
+            attempt += 1;
+        }
+    }
+
+    async fn translate(&self, text: &str, languages: &LanguagePair) -> Result<String> {
+        self.translate_with_context(text, languages, "").await
+    }
+
+    async fn translate_with_context(&self, text: &str, languages: &LanguagePair, context: &str) -> Result<String> {
+        Ok(self.translate_named(text, languages, context).await?.1)
+    }
+
+    async fn translate_named(
+        &self,
+        text: &str,
+        languages: &LanguagePair,
+        context: &str,
+    ) -> Result<(Responder, String)> {
+        let mut attempt = 1;
+        loop {
+            let error = match self.inner.translate_named(text, languages, context).await {
+                Ok(answer) => return Ok(answer),
+                Err(error) => error,
+            };
+
+            match self.retry_delay(attempt, &error) {
+                Some(delay) => self.wait(attempt, delay, &error).await,
+                None => return Err(error),
+            }
+            attempt += 1;
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn api_base(&self) -> &str {
+        self.inner.api_base()
+    }
+
+    fn api_key(&self) -> &str {
+        self.inner.api_key()
+    }
+
+    fn supports_placeholders(&self) -> bool {
+        self.inner.supports_placeholders()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::fake::FakeProvider;
+
+    fn config(max_attempts: u32) -> RetryConfig {
+        RetryConfig {
+            max_attempts,
+            base_delay_ms: 1,
+            max_delay_ms: 4,
+        }
+    }
+
+    fn retry(inner: &Arc<FakeProvider>, max_attempts: u32) -> RetryProvider {
+        RetryProvider::new(Arc::clone(inner) as Arc<dyn TranslationProvider>, &config(max_attempts), false)
+    }
+
+    fn api_error(status: u16, retry_after: Option<Duration>) -> anyhow::Error {
+        ProviderError::Api {
+            status,
+            reason: String::new(),
+            body: String::new(),
+            retry_after,
+        }
+        .into()
+    }
+
+    #[test]
+    fn backoff_grows_and_is_capped() {
+        let provider = RetryProvider::new(
+            Arc::new(FakeProvider::new("fake", vec![])),
+            &RetryConfig {
+                max_attempts: 10,
+                base_delay_ms: 100,
+                max_delay_ms: 1000,
+            },
+            false,
+        );
+        for _ in 0..20 {
+            let first = provider.backoff(1).as_millis();
+            assert!((50..=100).contains(&first), "{}", first);
+            let third = provider.backoff(3).as_millis();
+            assert!((200..=400).contains(&third), "{}", third);
+            let capped = provider.backoff(30).as_millis();
+            assert!((500..=1000).contains(&capped), "{}", capped);
+        }
+    }
+
+    #[test]
+    fn honors_retry_after_up_to_limit() {
+        let provider = retry(&Arc::new(FakeProvider::new("fake", vec![])), 3);
+        let delay = provider.retry_delay(1, &api_error(429, Some(Duration::from_secs(2))));
+        assert_eq!(delay, Some(Duration::from_secs(2)));
+        assert_eq!(provider.retry_delay(1, &api_error(429, Some(Duration::from_secs(600)))), None);
+    }
+
+    #[test]
+    fn stops_on_permanent_errors_and_attempt_limit() {
+        let provider = retry(&Arc::new(FakeProvider::new("fake", vec![])), 3);
+        assert_eq!(provider.retry_delay(1, &api_error(401, None)), None);
+        assert!(provider.retry_delay(2, &api_error(503, None)).is_some());
+        assert_eq!(provider.retry_delay(3, &api_error(503, None)), None);
+    }
+
+    #[tokio::test]
+    async fn retries_until_success() {
+        let inner = Arc::new(FakeProvider::new("fake", vec![Err(429), Err(502), Ok("done")]));
+        let translation = retry(&inner, 3).translate("text", &LanguagePair::default()).await.unwrap();
+        assert_eq!(translation, "done");
+        assert_eq!(inner.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn returns_permanent_error_without_retrying() {
+        let inner = Arc::new(FakeProvider::new("fake", vec![Err(400)]));
+        let error = retry(&inner, 3).translate("text", &LanguagePair::default()).await.unwrap_err();
+        assert_eq!(error.downcast_ref::<ProviderError>().unwrap().status(), Some(400));
+        assert_eq!(inner.calls(), 1);
+    }
+}
@@ -1,87 +1,275 @@
-use crate::app::{App, DisplayMode, MessageStatus};
+use super::search;
+use crate::app::{App, DisplayMode, Message, MessageStatus, WrappedHeight};
+use crate::utils::fuzzy_match;
 use ratatui::{
-    layout::Rect,
+    layout::{Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
     Frame,
 };
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use unicode_width::UnicodeWidthChar;
+
+/// 对比模式列之间的分隔符
+const COLUMN_SEPARATOR: &str = " │ ";
 
 pub fn render(frame: &mut Frame, app: &App, area: Rect) {
-    let mut lines: Vec<Line> = Vec::new();
+    let width = area.width.saturating_sub(2).max(1) as usize;
+    let height = area.height.saturating_sub(2) as usize;
 
-    // Calculate visible messages based on scroll
-    let visible_count = (area.height.saturating_sub(2)) as usize; // -2 for borders
-    let total_messages = app.messages.len();
-    let start_idx = if total_messages > visible_count {
-        app.scroll.min(total_messages.saturating_sub(visible_count))
-    } else {
-        0
-    };
+    // 各消息折行后的行数（按宽度与内容缓存），消息之间空一行
+    let mut starts = Vec::with_capacity(app.messages.len());
+    let mut heights = Vec::with_capacity(app.messages.len());
+    let mut total = 0;
+    {
+        let mut viewport = app.viewport.borrow_mut();
+        let mut cached = std::mem::take(&mut viewport.heights);
+        for (idx, message) in app.messages.iter().enumerate() {
+            let key = layout_key(app, idx, message);
+            let lines = match cached.remove(&message.id) {
+                Some(entry) if entry.width == width && entry.key == key => entry.lines,
+                _ => message_lines(app, idx, message, width).len(),
+            };
+            viewport.heights.insert(message.id, WrappedHeight { width, key, lines });
 
-    for (idx, message) in app.messages.iter().enumerate().skip(start_idx) {
-        // Add separator between messages
-        if idx > start_idx {
-            lines.push(Line::from(""));
+            total += usize::from(idx > 0);
+            starts.push(total);
+            heights.push(lines);
+            total += lines;
         }
+        viewport.height = height;
+        viewport.total = total;
+        viewport.starts = starts.clone();
+    }
+    let offset = app.scroll_offset();
 
-        // Message number and status indicator
-        let status_indicator = match &message.status {
-            MessageStatus::Pending => "⏳",
-            MessageStatus::Streaming => "⚡",
-            MessageStatus::Success => "✓",
-            MessageStatus::Error(_) => "✗",
-        };
-
-        let number_style = Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD);
-
-        lines.push(Line::from(vec![
-            Span::styled(format!("[{}] ", idx + 1), number_style),
-            Span::raw(status_indicator),
-            Span::raw(" "),
-            Span::styled(
-                message.timestamp.format("%H:%M:%S").to_string(),
-                Style::default().fg(Color::DarkGray),
-            ),
-        ]));
-
-        // Render based on display mode
-        match app.display_mode {
-            DisplayMode::TranslationOnly => {
-                render_translation_only(&mut lines, message);
-            }
-            DisplayMode::Bilingual => {
-                render_bilingual(&mut lines, message);
-            }
-            DisplayMode::OriginalOnly => {
-                render_original_only(&mut lines, message);
-            }
+    // 只为可见的消息生成内容
+    let mut lines: Vec<Line> = Vec::new();
+    let mut first_row = offset;
+    for (idx, message) in app.messages.iter().enumerate() {
+        let block_start = starts[idx] - usize::from(idx > 0);
+        if starts[idx] + heights[idx] <= offset {
+            continue;
         }
-
-        // Show error if any
-        if let MessageStatus::Error(ref err) = message.status {
-            lines.push(Line::from(vec![
-                Span::styled("Error: ", Style::default().fg(Color::Red)),
-                Span::styled(err.clone(), Style::default().fg(Color::Red)),
-            ]));
+        if block_start >= offset + height {
+            break;
+        }
+        if lines.is_empty() {
+            first_row = block_start;
+        }
+        if idx > 0 {
+            lines.push(Line::from(""));
         }
+        lines.extend(message_lines(app, idx, message, width));
     }
 
     let block = Block::default()
         .borders(Borders::ALL)
-        .title(format!(" Translation History ({}) ", app.provider_name))
+        .title(match &app.profile_name {
+            Some(profile) => format!(
+                " Translation History ({}: {}, {}) ",
+                profile, app.provider_name, app.languages
+            ),
+            None => format!(
+                " Translation History ({}, {}) ",
+                app.provider_name, app.languages
+            ),
+        })
         .border_style(Style::default().fg(Color::White));
 
     let paragraph = Paragraph::new(lines)
         .block(block)
-        .wrap(Wrap { trim: false });
+        .scroll(((offset - first_row).min(u16::MAX as usize) as u16, 0));
 
     frame.render_widget(paragraph, area);
+
+    // 内容超出时在右边框上显示滚动条
+    if total > height {
+        let mut state = ScrollbarState::new(total.saturating_sub(height)).position(offset);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            area.inner(&Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut state,
+        );
+    }
+}
+
+/// 消息按width折行后的各行
+fn message_lines(app: &App, idx: usize, message: &Message, width: usize) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    render_message(&mut lines, app, idx, message, width);
+    lines.into_iter().flat_map(|line| wrap_line(line, width)).collect()
+}
+
+/// 影响消息折行结果的状态；不变时沿用缓存的行数
+fn layout_key(app: &App, idx: usize, message: &Message) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (idx, app.selected == Some(idx), app.config.display.show_line_numbers).hash(&mut hasher);
+    (std::mem::discriminant(&app.display_mode), &app.highlight).hash(&mut hasher);
+    (&message.text, &message.translation, &message.provider, message.provider == app.provider_name).hash(&mut hasher);
+    hash_status(&message.status, &mut hasher);
+    message.selected.hash(&mut hasher);
+    for candidate in &message.candidates {
+        (&candidate.label, &candidate.translation).hash(&mut hasher);
+        hash_status(&candidate.status, &mut hasher);
+    }
+    hasher.finish()
+}
+
+fn hash_status(status: &MessageStatus, hasher: &mut DefaultHasher) {
+    std::mem::discriminant(status).hash(hasher);
+    if let MessageStatus::Error(error) = status {
+        error.hash(hasher);
+    }
+}
+
+/// 按显示宽度折行，尽量在空白处断开，保留各段样式
+fn wrap_line(line: Line, width: usize) -> Vec<Line<'static>> {
+    let cells: Vec<(char, Style)> = line
+        .spans
+        .iter()
+        .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+        .collect();
+
+    let mut rows = Vec::new();
+    let mut row: Vec<(char, Style)> = Vec::new();
+    let mut row_width = 0;
+    // 当前行中最后一个空白之后的位置
+    let mut break_at = None;
+    for (c, style) in cells {
+        let char_width = c.width().unwrap_or(0);
+        if row_width + char_width > width && !row.is_empty() {
+            if c.is_whitespace() {
+                // 行尾的空白不移到下一行
+                rows.push(std::mem::take(&mut row));
+                row_width = 0;
+                break_at = None;
+                continue;
+            }
+            let rest = match break_at.take() {
+                Some(at) if at < row.len() => row.split_off(at),
+                _ => Vec::new(),
+            };
+            rows.push(std::mem::replace(&mut row, rest));
+            row_width = row.iter().map(|(c, _)| c.width().unwrap_or(0)).sum();
+        }
+        row.push((c, style));
+        row_width += char_width;
+        if c.is_whitespace() {
+            break_at = Some(row.len());
+        }
+    }
+    rows.push(row);
+
+    rows.into_iter()
+        .map(|row| {
+            let mut spans: Vec<Span<'static>> = Vec::new();
+            for (c, style) in row {
+                match spans.last_mut() {
+                    Some(span) if span.style == style => span.content.to_mut().push(c),
+                    _ => spans.push(Span::styled(c.to_string(), style)),
+                }
+            }
+            Line {
+                spans,
+                style: line.style,
+                alignment: line.alignment,
+            }
+        })
+        .collect()
 }
 
-fn render_translation_only(lines: &mut Vec<Line>, message: &crate::app::Message) {
+fn render_message(lines: &mut Vec<Line>, app: &App, idx: usize, message: &Message, width: usize) {
+    // Message number and status indicator
+    let status_indicator = match &message.status {
+        MessageStatus::Pending => "⏳",
+        MessageStatus::Streaming => "⚡",
+        MessageStatus::Success => "✓",
+        MessageStatus::Error(_) => "✗",
+        MessageStatus::Cancelled => "⊘",
+    };
+
+    let number_style = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+
+    let mut header = Vec::new();
+    if app.selected == Some(idx) {
+        header.push(Span::styled(
+            "▶ ",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if app.config.display.show_line_numbers {
+        header.push(Span::styled(format!("[{}] ", idx + 1), number_style));
+    }
+    header.extend([
+        Span::raw(status_indicator),
+        Span::raw(" "),
+        Span::styled(
+            message.timestamp.format("%H:%M:%S").to_string(),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+    // 由fallback或切换profile前的Provider应答时标出
+    if !message.is_compare() && message.provider != app.provider_name {
+        header.push(Span::styled(
+            format!(" via {}", message.provider),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    lines.push(Line::from(header));
+
+    // 跳转搜索结果后高亮匹配的字符
+    let query = app.highlight.as_deref();
+
+    // Compare mode: one column per provider
+    if message.is_compare() {
+        if app.display_mode != DisplayMode::TranslationOnly {
+            render_original_only(lines, message, query);
+        }
+        if app.display_mode != DisplayMode::OriginalOnly {
+            render_compare(lines, message, width);
+        }
+        return;
+    }
+
+    // Render based on display mode
+    match app.display_mode {
+        DisplayMode::TranslationOnly => {
+            render_translation_only(lines, message, query);
+        }
+        DisplayMode::Bilingual => {
+            render_bilingual(lines, message, query);
+        }
+        DisplayMode::OriginalOnly => {
+            render_original_only(lines, message, query);
+        }
+    }
+
+    // Show error if any
+    if let MessageStatus::Error(ref err) = message.status {
+        lines.push(Line::from(vec![
+            Span::styled("Error: ", Style::default().fg(Color::Red)),
+            Span::styled(err.clone(), Style::default().fg(Color::Red)),
+        ]));
+    }
+    if message.status == MessageStatus::Cancelled {
+        lines.push(Line::from(Span::styled(
+            "Cancelled",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+}
+
+fn render_translation_only(lines: &mut Vec<Line>, message: &Message, query: Option<&str>) {
     if message.translation.is_empty() && message.status == MessageStatus::Streaming {
         lines.push(Line::from(Span::styled(
             "Translating...",
@@ -92,41 +280,255 @@ fn render_translation_only(lines: &mut Vec<Line>, message: &crate::app::Message)
         if message.status == MessageStatus::Streaming {
             translation.push('▊'); // Streaming cursor
         }
-        lines.push(Line::from(Span::styled(
-            translation,
-            Style::default().fg(Color::Green),
-        )));
+        push_text(lines, None, &translation, Style::default().fg(Color::Green), query);
     }
 }
 
-fn render_bilingual(lines: &mut Vec<Line>, message: &crate::app::Message) {
+fn render_bilingual(lines: &mut Vec<Line>, message: &Message, query: Option<&str>) {
     // Original text
-    lines.push(Line::from(vec![
-        Span::styled("  Original: ", Style::default().fg(Color::Blue)),
-        Span::raw(message.text.clone()),
-    ]));
+    push_text(
+        lines,
+        Some(("  Original: ", Style::default().fg(Color::Blue))),
+        &message.text,
+        Style::default(),
+        query,
+    );
 
     // Translation
+    let label = Some(("  Translation: ", Style::default().fg(Color::Green)));
     if message.translation.is_empty() && message.status == MessageStatus::Streaming {
-        lines.push(Line::from(vec![
-            Span::styled("  Translation: ", Style::default().fg(Color::Green)),
-            Span::styled("...", Style::default().fg(Color::Yellow)),
-        ]));
+        push_text(lines, label, "...", Style::default().fg(Color::Yellow), None);
     } else {
         let mut translation = message.translation.clone();
         if message.status == MessageStatus::Streaming {
             translation.push('▊'); // Streaming cursor
         }
-        lines.push(Line::from(vec![
-            Span::styled("  Translation: ", Style::default().fg(Color::Green)),
-            Span::raw(translation),
-        ]));
+        push_text(lines, label, &translation, Style::default(), query);
+    }
+}
+
+fn render_original_only(lines: &mut Vec<Line>, message: &Message, query: Option<&str>) {
+    push_text(lines, None, &message.text, Style::default().fg(Color::Blue), query);
+}
+
+/// 多行文本逐行输出；label只加在第一行，后续行缩进对齐；query匹配的字符高亮
+fn push_text(
+    lines: &mut Vec<Line>,
+    label: Option<(&'static str, Style)>,
+    text: &str,
+    style: Style,
+    query: Option<&str>,
+) {
+    let positions = query
+        .and_then(|query| fuzzy_match(query, text))
+        .map(|(_, positions)| positions)
+        .unwrap_or_default();
+    let mut line_start = 0; // 当前行首的字符下标
+
+    for (index, line) in text.split('\n').enumerate() {
+        let mut spans = Vec::new();
+        if let Some((label, label_style)) = label {
+            if index == 0 {
+                spans.push(Span::styled(label, label_style));
+            } else {
+                spans.push(Span::raw(" ".repeat(label.len())));
+            }
+        }
+        let count = line.chars().count();
+        let local: Vec<usize> = positions
+            .iter()
+            .filter(|&&p| p >= line_start && p < line_start + count)
+            .map(|p| p - line_start)
+            .collect();
+        spans.extend(search::highlighted(line, &local, style));
+        lines.push(Line::from(spans));
+        line_start += count + 1;
     }
 }
 
-fn render_original_only(lines: &mut Vec<Line>, message: &crate::app::Message) {
-    lines.push(Line::from(Span::styled(
-        message.text.clone(),
-        Style::default().fg(Color::Blue),
-    )));
+/// 对比模式：各Provider的结果并排显示，列头包含耗时
+fn render_compare(lines: &mut Vec<Line>, message: &Message, width: usize) {
+    let count = message.candidates.len();
+    let separators = COLUMN_SEPARATOR.chars().count() * (count - 1);
+    let column_width = (width.saturating_sub(separators) / count).max(10);
+
+    let mut headers = Vec::new();
+    let mut stats = Vec::new();
+    let mut bodies = Vec::new();
+
+    for (index, candidate) in message.candidates.iter().enumerate() {
+        let selected = index == message.selected;
+        let marker = if selected { "▶ " } else { "  " };
+        headers.push((
+            fit(&format!("{}{}. {}", marker, index + 1, candidate.label), column_width),
+            if selected {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Yellow)
+            },
+        ));
+
+        let elapsed = candidate.elapsed.unwrap_or_else(|| candidate.started.elapsed());
+        let stat = match (&candidate.status, candidate.first_token) {
+            (MessageStatus::Success, Some(first)) => format!(
+                "✓ {:.2}s (first token {:.2}s)",
+                elapsed.as_secs_f32(),
+                first.as_secs_f32()
+            ),
+            (MessageStatus::Success, None) => format!("✓ {:.2}s", elapsed.as_secs_f32()),
+            (MessageStatus::Error(_), _) => format!("✗ {:.2}s", elapsed.as_secs_f32()),
+            (MessageStatus::Cancelled, _) => format!("⊘ {:.2}s cancelled", elapsed.as_secs_f32()),
+            _ => format!("⚡ {:.1}s", elapsed.as_secs_f32()),
+        };
+        stats.push((fit(&stat, column_width), Style::default().fg(Color::DarkGray)));
+
+        let (text, style) = match &candidate.status {
+            MessageStatus::Error(error) => (format!("Error: {}", error), Style::default().fg(Color::Red)),
+            MessageStatus::Streaming if candidate.translation.is_empty() => {
+                ("...".to_string(), Style::default().fg(Color::Yellow))
+            }
+            MessageStatus::Streaming => (
+                format!("{}▊", candidate.translation),
+                Style::default().fg(Color::Green),
+            ),
+            _ if selected => (candidate.translation.clone(), Style::default().fg(Color::Green)),
+            _ => (candidate.translation.clone(), Style::default()),
+        };
+        bodies.push(
+            wrap(&text, column_width)
+                .into_iter()
+                .map(|line| (fit(&line, column_width), style))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    lines.push(join_columns(headers));
+    lines.push(join_columns(stats));
+
+    let rows = bodies.iter().map(Vec::len).max().unwrap_or(0);
+    for row in 0..rows {
+        let cells = bodies
+            .iter()
+            .map(|body| {
+                body.get(row)
+                    .cloned()
+                    .unwrap_or_else(|| (" ".repeat(column_width), Style::default()))
+            })
+            .collect();
+        lines.push(join_columns(cells));
+    }
+}
+
+fn join_columns(cells: Vec<(String, Style)>) -> Line<'static> {
+    let mut spans = Vec::new();
+    for (index, (text, style)) in cells.into_iter().enumerate() {
+        if index > 0 {
+            spans.push(Span::styled(COLUMN_SEPARATOR, Style::default().fg(Color::DarkGray)));
+        }
+        spans.push(Span::styled(text, style));
+    }
+    Line::from(spans)
+}
+
+/// 按显示宽度折行（CJK字符占两列）
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut line_width = 0;
+        for c in paragraph.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if line_width + char_width > width && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+            line.push(c);
+            line_width += char_width;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// 截断或补空格到固定显示宽度
+fn fit(text: &str, width: usize) -> String {
+    let mut result = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if used + char_width > width {
+            break;
+        }
+        result.push(c);
+        used += char_width;
+    }
+    result.push_str(&" ".repeat(width - used));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::message::Candidate;
+    use crate::providers::fake::FakeProvider;
+    use std::sync::Arc;
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|span| span.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn wraps_at_whitespace() {
+        let lines = wrap_line(Line::from("hello brave new world"), 11);
+        let rows: Vec<String> = lines.iter().map(text).collect();
+        assert_eq!(rows, vec!["hello brave", "new world"]);
+    }
+
+    #[test]
+    fn breaks_long_words_and_wide_characters() {
+        let rows: Vec<String> = wrap_line(Line::from("abcdefgh"), 3).iter().map(text).collect();
+        assert_eq!(rows, vec!["abc", "def", "gh"]);
+
+        // CJK字符占两列
+        let rows: Vec<String> = wrap_line(Line::from("你好世界"), 5).iter().map(text).collect();
+        assert_eq!(rows, vec!["你好", "世界"]);
+    }
+
+    #[test]
+    fn keeps_span_styles_across_rows() {
+        let red = Style::default().fg(Color::Red);
+        let line = Line::from(vec![Span::raw("ab "), Span::styled("cdef", red)]);
+        let lines = wrap_line(line, 4);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].spans[0].content, "ab ");
+        assert_eq!(lines[1].spans, vec![Span::styled("cdef", red)]);
+    }
+
+    #[test]
+    fn empty_line_takes_one_row() {
+        assert_eq!(wrap_line(Line::from(""), 10).len(), 1);
+    }
+
+    #[test]
+    fn layout_key_follows_content_of_equal_length() {
+        let app = App::for_tests(Arc::new(FakeProvider::new("fake", vec![])));
+        let mut message = Message::new(0, "cat".to_string(), "fake".to_string());
+        message.translation = "猫".to_string();
+        let key = layout_key(&app, 0, &message);
+        assert_eq!(layout_key(&app, 0, &message.clone()), key);
+
+        let mut edited = message.clone();
+        edited.text = "dog".to_string();
+        assert_ne!(layout_key(&app, 0, &edited), key);
+
+        let mut retranslated = message.clone();
+        retranslated.translation = "狗".to_string();
+        assert_ne!(layout_key(&app, 0, &retranslated), key);
+
+        let mut compared = message.clone();
+        compared.candidates.push(Candidate::new("a".to_string()));
+        let key = layout_key(&app, 0, &compared);
+        compared.candidates[0].translation = "b".to_string();
+        assert_ne!(layout_key(&app, 0, &compared), key);
+    }
 }
@@ -0,0 +1,50 @@
+use crate::app::App;
+use crate::history::ExportFormat;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// 导出格式选择弹窗
+pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(selected) = app.export_popup else {
+        return;
+    };
+
+    let mut lines: Vec<Line> = Vec::new();
+    for (idx, format) in ExportFormat::ALL.iter().enumerate() {
+        let style = if idx == selected {
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {}", format.name()), style),
+            Span::styled(format!("  .{}", format.extension()), Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+
+    let width = area.width.min(60);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Export Session (↑↓ Select, Enter Save, ESC Close) ")
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
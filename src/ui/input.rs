@@ -6,23 +6,85 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
+use unicode_width::UnicodeWidthChar;
+
+/// 输入框最多显示的行数，超出时随光标滚动
+const MAX_ROWS: usize = 8;
+
+/// 输入框高度（含边框），随内容行数增长
+pub fn height(app: &App, width: u16) -> u16 {
+    if app.history_search.is_some() {
+        return 3;
+    }
+    let (rows, _) = app.input.wrap(width.saturating_sub(2) as usize);
+    rows.len().clamp(1, MAX_ROWS) as u16 + 2
+}
 
 pub fn render(frame: &mut Frame, app: &App, area: Rect) {
-    let input_text = if app.input.is_empty() {
-        vec![Span::styled(
+    if app.history_search.is_some() {
+        render_search(frame, app, area);
+        return;
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Input (Shift/Alt+Enter: New Line) ")
+        .border_style(Style::default().fg(Color::Cyan));
+
+    if app.input.is_empty() {
+        let placeholder = Line::from(Span::styled(
             "Type your text here and press Enter to translate...",
             Style::default().fg(Color::DarkGray),
-        )]
-    } else {
-        vec![Span::raw(app.input.clone()), Span::raw("▊")]
+        ));
+        frame.render_widget(Paragraph::new(placeholder).block(block), area);
+        set_cursor(frame, app, area, (0, 0));
+        return;
+    }
+
+    let (rows, (row, column)) = app.input.wrap(area.width.saturating_sub(2) as usize);
+    let offset = row.saturating_sub(MAX_ROWS - 1);
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .skip(offset)
+        .take(MAX_ROWS)
+        .map(Line::from)
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+    set_cursor(frame, app, area, (row - offset, column));
+}
+
+/// Ctrl+R 反向搜索：显示搜索词与最近的匹配项
+fn render_search(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(search) = &app.history_search else {
+        return;
     };
+    let matched = search.matched.and_then(|index| app.input_history.get(index));
+
+    let prompt = format!("(reverse-i-search)`{}': ", search.query);
+    let mut spans = vec![Span::styled(prompt.clone(), Style::default().fg(Color::Yellow))];
+    match matched {
+        Some(text) => spans.push(Span::raw(text.replace('\n', " ⏎ "))),
+        None if !search.query.is_empty() => {
+            spans.push(Span::styled("no match", Style::default().fg(Color::Red)))
+        }
+        None => {}
+    }
 
     let block = Block::default()
         .borders(Borders::ALL)
-        .title(" Input ")
-        .border_style(Style::default().fg(Color::Cyan));
+        .title(" History Search (Ctrl+R: Older, Enter: Accept, ESC: Cancel) ")
+        .border_style(Style::default().fg(Color::Yellow));
 
-    let paragraph = Paragraph::new(Line::from(input_text)).block(block);
+    frame.render_widget(Paragraph::new(Line::from(spans)).block(block), area);
+    let column = prompt.chars().map(|c| c.width().unwrap_or(0)).sum::<usize>() - 3;
+    set_cursor(frame, app, area, (0, column));
+}
 
-    frame.render_widget(paragraph, area);
+/// 显示终端光标（按显示宽度定位，CJK字符占两列）；弹窗打开时隐藏
+fn set_cursor(frame: &mut Frame, app: &App, area: Rect, (row, column): (usize, usize)) {
+    let popup_open = app.profile_popup.is_some() || app.model_popup.is_some() || app.export_popup.is_some();
+    if !popup_open && app.search.is_none() {
+        frame.set_cursor(area.x + 1 + column as u16, area.y + 1 + row as u16);
+    }
 }
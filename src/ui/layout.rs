@@ -4,14 +4,15 @@ use ratatui::{
     Frame,
 };
 
-use super::{chat, input, statusbar};
+use super::{chat, export, input, models, profiles, search, statusbar};
 
 pub fn render_ui(frame: &mut Frame, app: &App) {
+    let input_height = input::height(app, frame.size().width);
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Min(5),     // Chat area
-            Constraint::Length(3),  // Input box
+            Constraint::Length(input_height), // Input box
             Constraint::Length(1),  // Status bar
         ])
         .split(frame.size());
@@ -19,6 +20,12 @@ pub fn render_ui(frame: &mut Frame, app: &App) {
     render_chat_area(frame, app, chunks[0]);
     render_input_area(frame, app, chunks[1]);
     render_status_bar(frame, app, chunks[2]);
+
+    // Popups
+    profiles::render(frame, app, chunks[0]);
+    models::render(frame, app, chunks[0]);
+    search::render(frame, app, chunks[0]);
+    export::render(frame, app, chunks[0]);
 }
 
 fn render_chat_area(frame: &mut Frame, app: &App, area: Rect) {
@@ -1,6 +1,11 @@
 pub mod chat;
+pub mod export;
 pub mod input;
 pub mod layout;
+pub mod models;
+pub mod profiles;
+pub mod search;
 pub mod statusbar;
+pub mod terminal;
 
 pub use layout::render_ui;
@@ -0,0 +1,65 @@
+use crate::app::App;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// 重新翻译时选择模型的弹窗
+pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(selected) = app.model_popup else {
+        return;
+    };
+
+    let choices = app.model_choices();
+    let mut lines: Vec<Line> = Vec::new();
+
+    for (idx, entry) in choices.iter().enumerate() {
+        // profile显示其provider与模型
+        let detail = app
+            .config
+            .profiles
+            .get(entry)
+            .map(|profile| {
+                format!(
+                    "  {} {}",
+                    profile.provider.as_deref().unwrap_or("openai"),
+                    profile.model.as_deref().unwrap_or("")
+                )
+            })
+            .unwrap_or_default();
+
+        let style = if idx == selected {
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {}", entry), style),
+            Span::styled(detail, Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+
+    let width = area.width.min(60);
+    let height = (choices.len() as u16 + 2).min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Regenerate with (↑↓ Select, Enter Run, ESC Close) ")
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
@@ -0,0 +1,59 @@
+use crate::app::App;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(selected) = app.profile_popup else {
+        return;
+    };
+
+    let names = app.profile_names();
+    let mut lines: Vec<Line> = Vec::new();
+
+    for (idx, name) in names.iter().enumerate() {
+        let profile = &app.config.profiles[name];
+        let current = app.profile_name.as_deref() == Some(name.as_str());
+        let marker = if current { "● " } else { "  " };
+        let detail = format!(
+            "  {} {}",
+            profile.provider.as_deref().unwrap_or("openai"),
+            profile.model.as_deref().unwrap_or("")
+        );
+
+        let style = if idx == selected {
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}{}", marker, name), style),
+            Span::styled(detail, Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+
+    let width = area.width.min(60);
+    let height = (names.len() as u16 + 2).min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Profiles (↑↓ Select, Enter Switch, ESC Close) ")
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
@@ -0,0 +1,105 @@
+use crate::app::{App, Hit};
+use chrono::Local;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// `/` 搜索浮层：搜索词与结果列表（匹配的字符高亮）
+pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(search) = &app.search else {
+        return;
+    };
+
+    let popup = Rect::new(
+        area.x + 2.min(area.width / 10),
+        area.y + 1.min(area.height / 10),
+        area.width.saturating_sub(4).max(area.width * 8 / 10),
+        area.height.saturating_sub(2).max(3),
+    );
+    let rows = popup.height.saturating_sub(4) as usize; // 边框、搜索词与空行
+
+    let prompt = format!("/ {}", search.query);
+    let mut lines = vec![
+        Line::from(Span::styled(prompt.clone(), Style::default().fg(Color::Yellow))),
+        Line::from(""),
+    ];
+
+    if search.results.is_empty() && !search.query.trim().is_empty() {
+        lines.push(Line::from(Span::styled("No matches", Style::default().fg(Color::DarkGray))));
+    }
+
+    let offset = search.selected.saturating_sub(rows.saturating_sub(1));
+    for (index, result) in search.results.iter().enumerate().skip(offset).take(rows) {
+        let (label, original, translation) = match result.hit {
+            Hit::Message(i) => {
+                let message = &app.messages[i];
+                (format!("#{:<4}", i + 1), &message.text, &message.translation)
+            }
+            Hit::Entry(i) => {
+                let entry = &search.entries[i];
+                (
+                    entry.timestamp.with_timezone(&Local).format("%m-%d").to_string(),
+                    &entry.original,
+                    &entry.translation,
+                )
+            }
+        };
+
+        let selected = index == search.selected;
+        let base = if selected {
+            Style::default().bg(Color::DarkGray)
+        } else {
+            Style::default()
+        };
+
+        let mut spans = vec![
+            Span::styled(if selected { "▶ " } else { "  " }, base.fg(Color::Cyan)),
+            Span::styled(format!("{} ", label), base.fg(Color::Cyan)),
+        ];
+        spans.extend(highlighted(&single_line(original), &result.original, base));
+        spans.push(Span::styled(" → ", base.fg(Color::DarkGray)));
+        spans.extend(highlighted(&single_line(translation), &result.translation, base.fg(Color::Green)));
+        lines.push(Line::from(spans));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Search (↑↓ Select, Enter Jump, Ctrl+Y Copy, ESC Close) ")
+        .border_style(Style::default().fg(Color::Yellow));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+    frame.set_cursor(popup.x + 1 + prompt.width() as u16, popup.y + 1);
+}
+
+/// 按字符下标高亮匹配部分
+pub fn highlighted(text: &str, positions: &[usize], style: Style) -> Vec<Span<'static>> {
+    let mark = style.fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_marked = false;
+
+    for (index, c) in text.chars().enumerate() {
+        let marked = positions.binary_search(&index).is_ok();
+        if marked != run_marked && !run.is_empty() {
+            let style = if run_marked { mark } else { style };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_marked = marked;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, if run_marked { mark } else { style }));
+    }
+    spans
+}
+
+/// 换行替换为空格（字符下标保持不变）
+fn single_line(text: &str) -> String {
+    text.replace('\n', " ")
+}
@@ -9,17 +9,48 @@ use ratatui::{
 
 pub fn render(frame: &mut Frame, app: &App, area: Rect) {
     let mode_text = format!("Mode: {}", app.display_mode.to_string());
-    let shortcuts: Vec<(&str, &str)> = vec![
-        ("Enter", "Send"),
-        ("TAB", &mode_text),
-        ("Ctrl+Y", "Copy Latest"),
-        ("1-9", "Copy #N"),
-        ("Ctrl+C", "Clear"),
-        ("ESC", "Quit"),
-    ];
+    let compare_text = if app.compare_mode { "Compare: On" } else { "Compare" };
+    let mut shortcuts: Vec<(&str, &str)> = if app.selected.is_some() {
+        vec![
+            ("Alt+↑↓", "Select"),
+            ("Ctrl+G", "Regenerate"),
+            ("Alt+G", "Other Model"),
+            ("Ctrl+E", "Edit"),
+            ("1-9", "Copy #N"),
+            ("ESC", "Deselect"),
+        ]
+    } else {
+        vec![
+            ("Enter", "Send"),
+            ("TAB", &mode_text),
+            ("Ctrl+Y", "Copy Latest"),
+            ("1-9", "Copy #N"),
+            ("Ctrl+T", compare_text),
+            ("Alt+↑↓", "Select"),
+            ("Ctrl+C", "Clear"),
+            ("ESC", "Quit"),
+        ]
+    };
+    if app.is_streaming() {
+        shortcuts.insert(shortcuts.len() - 1, ("Ctrl+X", "Cancel"));
+    }
+    if !app.config.profiles.is_empty() && app.selected.is_none() {
+        shortcuts.insert(shortcuts.len() - 1, ("Ctrl+P", "Profile"));
+    }
 
     let mut spans = Vec::new();
 
+    // 通知显示在最前，避免被窄终端截断
+    if let Some(notification) = app.get_notification() {
+        spans.push(Span::styled(
+            format!("ℹ {}", notification),
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw(" | "));
+    }
+
     for (i, (key, desc)) in shortcuts.iter().enumerate() {
         if i > 0 {
             spans.push(Span::raw(" | "));
@@ -34,17 +65,6 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect) {
         spans.push(Span::raw(desc.to_string()));
     }
 
-    // Show notification if any
-    if let Some(notification) = app.get_notification() {
-        spans.push(Span::raw(" | "));
-        spans.push(Span::styled(
-            format!("ℹ {}", notification),
-            Style::default()
-                .fg(Color::Green)
-                .add_modifier(Modifier::BOLD),
-        ));
-    }
-
     let paragraph = Paragraph::new(Line::from(spans))
         .style(Style::default().bg(Color::Black).fg(Color::White));
 
@@ -0,0 +1,60 @@
+use crossterm::{
+    event::{
+        DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 是否捕获鼠标（开启后滚轮可滚动聊天记录，但终端自身的文字选择需按住Shift）
+static MOUSE_CAPTURE: AtomicBool = AtomicBool::new(false);
+
+pub fn mouse_capture() -> bool {
+    MOUSE_CAPTURE.load(Ordering::SeqCst)
+}
+
+/// 开启或关闭鼠标捕获，立即生效
+pub fn set_mouse_capture(enabled: bool) -> io::Result<()> {
+    MOUSE_CAPTURE.store(enabled, Ordering::SeqCst);
+    if enabled {
+        execute!(io::stdout(), EnableMouseCapture)
+    } else {
+        execute!(io::stdout(), DisableMouseCapture)
+    }
+}
+
+/// 进入全屏界面：raw模式、备用屏幕、括号粘贴与（按设置）鼠标事件
+pub fn enter(enhanced: bool) -> io::Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
+    if mouse_capture() {
+        execute!(stdout, EnableMouseCapture)?;
+    }
+    if enhanced {
+        execute!(
+            stdout,
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )?;
+    }
+    Ok(())
+}
+
+/// 退出全屏界面并恢复终端
+pub fn leave(enhanced: bool) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    if enhanced {
+        execute!(stdout, PopKeyboardEnhancementFlags)?;
+    }
+    disable_raw_mode()?;
+    execute!(
+        stdout,
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        LeaveAlternateScreen,
+        crossterm::cursor::Show
+    )
+}
@@ -0,0 +1,9 @@
+use arboard::Clipboard;
+
+/// 复制文本到系统剪贴板，成功返回true
+pub fn copy_text(text: &str) -> bool {
+    match Clipboard::new() {
+        Ok(mut clipboard) => clipboard.set_text(text).is_ok(),
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,80 @@
+/// 模糊匹配：query的字符（忽略空白、大小写）须按顺序出现在text中。
+/// 按字符而非字节处理，CJK文本可逐字匹配。返回得分与匹配到的字符下标。
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).map(fold).collect();
+    if query.is_empty() {
+        return None;
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut positions = Vec::with_capacity(query.len());
+    let mut score = 0;
+    let mut next = 0;
+
+    for &wanted in &query {
+        let offset = chars[next..].iter().position(|&c| fold(c) == wanted)?;
+        let index = next + offset;
+
+        score += 16;
+        match positions.last() {
+            Some(&last) if last + 1 == index => score += 12, // 连续匹配
+            Some(_) => score -= (offset as i64).min(8),      // 间隔越大分越低
+            None => score -= (index as i64).min(8),          // 越靠前越好
+        }
+        if index == 0 || is_boundary(chars[index - 1]) || is_cjk(chars[index]) {
+            score += 6;
+        }
+
+        positions.push(index);
+        next = index + 1;
+    }
+    Some((score, positions))
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn is_boundary(c: char) -> bool {
+    c.is_whitespace() || c.is_ascii_punctuation()
+}
+
+/// CJK统一表意文字、假名与谚文（无词间空格，每个字都视为词首）
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30ff}' | '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}'
+        | '\u{ac00}'..='\u{d7af}' | '\u{f900}'..='\u{faff}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_in_order_ignoring_case_and_spaces() {
+        assert_eq!(fuzzy_match("HW", "hello world").map(|(_, p)| p), Some(vec![0, 6]));
+        assert_eq!(fuzzy_match("h w", "hello world").map(|(_, p)| p), Some(vec![0, 6]));
+        assert!(fuzzy_match("wh", "hello world").is_none());
+        assert!(fuzzy_match("xyz", "hello world").is_none());
+    }
+
+    #[test]
+    fn empty_query_matches_nothing() {
+        assert!(fuzzy_match("", "hello").is_none());
+        assert!(fuzzy_match("   ", "hello").is_none());
+    }
+
+    #[test]
+    fn positions_are_char_indices() {
+        assert_eq!(fuzzy_match("世界", "你好，世界").map(|(_, p)| p), Some(vec![3, 4]));
+        assert_eq!(fuzzy_match("é", "café").map(|(_, p)| p), Some(vec![3]));
+    }
+
+    #[test]
+    fn prefers_consecutive_and_word_start_matches() {
+        let score = |query, text| fuzzy_match(query, text).unwrap().0;
+        assert!(score("tran", "translate") > score("tran", "t r a n"));
+        assert!(score("world", "hello world") > score("world", "helloworld"));
+        assert!(score("ab", "ab cd") > score("ab", "cd ab"));
+    }
+}
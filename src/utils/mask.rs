@@ -0,0 +1,14 @@
+/// 遮盖敏感信息，仅保留前prefix和后suffix个字符
+pub fn mask_sensitive(s: &str, prefix: usize, suffix: usize) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() <= prefix + suffix {
+        return "*".repeat(chars.len());
+    }
+    let mask_len = chars.len() - prefix - suffix;
+    format!(
+        "{}{}{}",
+        chars[..prefix].iter().collect::<String>(),
+        "*".repeat(mask_len),
+        chars[chars.len() - suffix..].iter().collect::<String>()
+    )
+}
@@ -0,0 +1,6 @@
+pub mod clipboard;
+pub mod fuzzy;
+pub mod mask;
+
+pub use fuzzy::fuzzy_match;
+pub use mask::mask_sensitive;